@@ -0,0 +1,29 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+
+use tictactoe::bitboard::BitBoard;
+use tictactoe::board::Board;
+use tictactoe::simulator::simulate;
+
+/// Compares checking the status of the same random final positions with both board representations.
+fn status(c: &mut Criterion) {
+    let games = simulate(1000, &mut StdRng::seed_from_u64(0), false);
+    let boards: Vec<Board> = games.into_iter().map(|game| game.board).collect();
+    let bitboards: Vec<BitBoard> = boards.iter().map(|board| BitBoard::try_from(board).unwrap()).collect();
+
+    let mut group = c.benchmark_group("status");
+    group.bench_function("board", |b| b.iter(|| {
+        for board in boards.iter() {
+            black_box(board.status());
+        }
+    }));
+    group.bench_function("bitboard", |b| b.iter(|| {
+        for board in bitboards.iter() {
+            black_box(board.status());
+        }
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, status);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use std::env;
+use std::io::{stdin, stdout, BufRead, Write};
+use std::thread;
+use std::time::Duration;
+
+use tictactoe::board::{Board, BoardIterator, Mark};
+use tictactoe::engine::{parse, EngineCommand};
+
+const USAGE: &str = "usage: stub-engine first|illegal|garbage|silent|crash";
+
+/// A deliberately simple or broken engine to test the arena with.
+///
+/// `first` plays the first free cell, `illegal` always plays the top left cell, `garbage` answers
+/// with nonsense, `silent` never answers `go`, and `crash` exits on `go`.
+fn main() {
+    let behavior = env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    });
+    let mut board = Board::new();
+    let mut output = stdout();
+    for line in stdin().lock().lines().map_while(Result::ok) {
+        match parse(&line) {
+            Ok(EngineCommand::IsReady) => writeln!(output, "readyok").unwrap(),
+            Ok(EngineCommand::NewGame) => board = Board::new(),
+            Ok(EngineCommand::Position(position)) => board = position,
+            Ok(EngineCommand::Go(_)) => match behavior.as_str() {
+                "first" => {
+                    let (i, j) = BoardIterator::new(&board).find(|(_, mark)| *mark == Mark::Empty).unwrap().0;
+                    writeln!(output, "info nodes 1\nbestmove {},{}", i, j).unwrap();
+                }
+                "illegal" => writeln!(output, "bestmove 0,0").unwrap(),
+                "garbage" => writeln!(output, "bestmove somewhere").unwrap(),
+                "silent" => thread::sleep(Duration::from_secs(60)),
+                _ => return,
+            },
+            Ok(EngineCommand::Quit) => return,
+            Err(error) => writeln!(output, "error {}", error).unwrap(),
+        }
+        output.flush().unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::{player_name, Board, Mark, Position, Status};
+use crate::engine::parse_turn;
+use crate::record::result;
+use crate::tournament::Score;
+
+/// Time limits of a match.
+#[derive(Copy, Clone, Debug)]
+pub struct Limits {
+    /// The time engines are asked to think about a turn.
+    pub movetime: Duration,
+    /// How long to wait for an answer before the engine forfeits.
+    pub timeout: Duration,
+}
+
+/// The reason an engine loses a game before it is over on the board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Forfeit {
+    Timeout,
+    Crashed,
+    IllegalMove(String),
+}
+
+impl fmt::Display for Forfeit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Forfeit::Timeout => write!(formatter, "no answer in time"),
+            Forfeit::Crashed => write!(formatter, "the engine has stopped"),
+            Forfeit::IllegalMove(answer) => write!(formatter, "illegal move: {}", answer),
+        }
+    }
+}
+
+/// An engine running as a child process that speaks the protocol of `crate::engine`.
+pub struct EngineProcess {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl EngineProcess {
+    /// Starts the engine from a command line like `./engine --flag`.
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or(io::Error::new(io::ErrorKind::InvalidInput, "empty engine command"))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("the input is piped");
+        let stdout = child.stdout.take().expect("the output is piped");
+
+        // Reading in a thread of its own lets the arena stop waiting for a line at any time.
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {command: String::from(command), child, stdin, lines})
+    }
+
+    pub fn command(&self) -> &str { &self.command }
+
+    /// Replaces the process with a new one, dropping whatever the old one was doing.
+    pub fn restart(&mut self) -> io::Result<()> {
+        *self = Self::spawn(&self.command)?;
+        Ok(())
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), Forfeit> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| Forfeit::Crashed)
+    }
+
+    /// Waits for a line starting with the word, skipping `info` and any other lines.
+    fn expect(&mut self, word: &str, timeout: Duration) -> Result<String, Forfeit> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) if line.split_whitespace().next() == Some(word) => return Ok(line),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return Err(Forfeit::Timeout),
+                Err(RecvTimeoutError::Disconnected) => return Err(Forfeit::Crashed),
+            }
+        }
+    }
+
+    /// Starts a new game and waits until the engine is ready for it.
+    fn prepare(&mut self, limits: &Limits) -> Result<(), Forfeit> {
+        self.send("newgame")?;
+        self.send("isready")?;
+        self.expect("readyok", limits.timeout).map(|_| ())
+    }
+
+    /// Asks for the turn after the given ones.
+    fn request_move(&mut self, start: &Board, moves: &[Position], limits: &Limits) -> Result<String, Forfeit> {
+        let mut position = format!("position {} win {}", start.notation(), start.win_length());
+        if !moves.is_empty() {
+            position.push_str(" moves");
+            for (i, j) in moves {
+                position.push_str(&format!(" {},{}", i, j));
+            }
+        }
+        self.send(&position)?;
+        self.send(&format!("go movetime {}", limits.movetime.as_millis()))?;
+        let answer = self.expect("bestmove", limits.timeout)?;
+        Ok(answer.trim_start_matches("bestmove").trim().to_string())
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A game between two engines.
+#[derive(Clone, Debug)]
+pub struct ArenaGame {
+    /// The index of the engine playing X.
+    pub x: usize,
+    pub board: Board,
+    pub moves: Vec<Position>,
+    /// `Status::Winner` or `Status::Tie`, taking forfeits into account.
+    pub status: Status,
+    /// The player who forfeited the game and why.
+    pub forfeit: Option<(bool, Forfeit)>,
+}
+
+/// Results of a match between two engines.
+#[derive(Clone, Debug)]
+pub struct ArenaReport {
+    pub names: [String; 2],
+    pub scores: [Score; 2],
+    pub games: Vec<ArenaGame>,
+}
+
+/// Plays N games between the engines starting from the given board, with the engines taking turns
+/// to move first.
+///
+/// An engine that runs out of time or stops loses the game and is restarted for the next one.
+pub fn run_match(engines: &mut [EngineProcess; 2], games: usize, board: &Board, limits: &Limits) -> ArenaReport {
+    let mut report = ArenaReport {
+        names: [0, 1].map(|i| String::from(engines[i].command())),
+        scores: [Score::default(); 2],
+        games: vec![],
+    };
+
+    for round in 0..games {
+        let x = round % 2;
+        let [fst, snd] = engines;
+        let game = if x == 0 { play(fst, snd, board, limits, x) } else { play(snd, fst, board, limits, x) };
+
+        let index = |first: bool| if first { x } else { 1 - x };
+        match game.status {
+            Status::Winner(first) => {
+                report.scores[index(first)].wins += 1;
+                report.scores[index(!first)].losses += 1;
+            }
+            _ => report.scores.iter_mut().for_each(|score| score.draws += 1),
+        }
+        if let Some((first, Forfeit::Timeout | Forfeit::Crashed)) = game.forfeit {
+            // A failed restart leaves the stopped process, which forfeits its next games too.
+            let _ = engines[index(first)].restart();
+        }
+        report.games.push(game);
+    }
+
+    report
+}
+
+/// Plays one game and adjudicates it by the board's status.
+fn play(x: &mut EngineProcess, o: &mut EngineProcess, start: &Board, limits: &Limits, x_index: usize) -> ArenaGame {
+    let mut game = ArenaGame {x: x_index, board: start.clone(), moves: vec![], status: Status::Ongoing, forfeit: None};
+    let forfeit = |game: &mut ArenaGame, first: bool, reason: Forfeit| {
+        game.status = Status::Winner(!first);
+        game.forfeit = Some((first, reason));
+    };
+
+    for (first, engine) in [(true, &mut *x), (false, &mut *o)] {
+        if let Err(reason) = engine.prepare(limits) {
+            forfeit(&mut game, first, reason);
+            return game;
+        }
+    }
+
+    while game.board.status() == Status::Ongoing {
+        let first = game.board.first_to_move();
+        let engine = if first { &mut *x } else { &mut *o };
+        let answer = match engine.request_move(start, &game.moves, limits) {
+            Ok(answer) => answer,
+            Err(reason) => {
+                forfeit(&mut game, first, reason);
+                return game;
+            }
+        };
+        match parse_turn(&answer).filter(|pos| game.board.contains(pos) && game.board.empty(pos)) {
+            Some(pos) => {
+                game.board.set(pos, Mark::of(first));
+                game.moves.push(pos);
+            }
+            None => {
+                forfeit(&mut game, first, Forfeit::IllegalMove(answer));
+                return game;
+            }
+        }
+    }
+
+    game.status = game.board.status();
+    game
+}
+
+impl fmt::Display for ArenaReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (round, game) in self.games.iter().enumerate() {
+            write!(
+                formatter, "Game {}: {} (X) vs {} (O): {} after {} turns",
+                round + 1, self.names[game.x], self.names[1 - game.x], result(game.status), game.moves.len(),
+            )?;
+            if let Some((first, reason)) = &game.forfeit {
+                write!(formatter, ", {} forfeits: {}", player_name(*first), reason)?;
+            }
+            writeln!(formatter)?;
+        }
+
+        let width = self.names.iter().map(|name| name.len()).max().unwrap_or(0).max(10);
+        writeln!(formatter, "\n{:<width$} {:>7} {:>7} {:>7} {:>7}", "", "Wins", "Losses", "Draws", "Points", width = width)?;
+        for (name, score) in self.names.iter().zip(self.scores.iter()) {
+            writeln!(
+                formatter, "{:<width$} {:>7} {:>7} {:>7} {:>7.1}",
+                name, score.wins, score.losses, score.draws, score.points(), width = width,
+            )?;
+        }
+        Ok(())
+    }
+}
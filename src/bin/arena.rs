@@ -0,0 +1,69 @@
+use std::env;
+use std::fs;
+use std::process::exit;
+use std::time::Duration;
+
+use tictactoe::arena::{run_match, EngineProcess, Limits};
+use tictactoe::board::Board;
+
+const USAGE: &str = "usage: arena ENGINE ENGINE [--games N] [--movetime MS] [--timeout MS] [--summary FILE]\n\
+                     engines are command lines like \"./target/release/engine\"";
+
+struct Options {
+    engines: Vec<String>,
+    games: usize,
+    limits: Limits,
+    summary: Option<String>,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    let spawn = |command: &String| EngineProcess::spawn(command).unwrap_or_else(|error| {
+        eprintln!("cannot start {}: {}", command, error);
+        exit(1);
+    });
+    let mut engines = [spawn(&options.engines[0]), spawn(&options.engines[1])];
+
+    let report = run_match(&mut engines, options.games, &Board::new(), &options.limits);
+    print!("{}", report);
+    if let Some(path) = options.summary {
+        if let Err(error) = fs::write(&path, report.to_string()) {
+            eprintln!("cannot write {}: {}", path, error);
+            exit(1);
+        }
+    }
+}
+
+/// Reads the two engines, the number of games (2 by default), and the time limits in milliseconds
+/// (100 to think and 1000 to answer by default).
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        engines: vec![],
+        games: 2,
+        limits: Limits {movetime: Duration::from_millis(100), timeout: Duration::from_millis(1000)},
+        summary: None,
+    };
+    let mut args = env::args().skip(1);
+    let number = |value: String| value.parse::<u64>().map_err(|_| format!("not a number: {}", value));
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            options.engines.push(arg);
+            continue;
+        }
+        let value = args.next().ok_or(format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--games" => options.games = number(value)? as usize,
+            "--movetime" => options.limits.movetime = Duration::from_millis(number(value)?),
+            "--timeout" => options.limits.timeout = Duration::from_millis(number(value)?),
+            "--summary" => options.summary = Some(value),
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    if options.engines.len() != 2 {
+        return Err(String::from("expected two engines"));
+    }
+    Ok(options)
+}
@@ -0,0 +1,12 @@
+use std::io::{stdin, stdout};
+use std::process::exit;
+
+use tictactoe::engine::Engine;
+
+/// Speaks the engine protocol over the standard input and output, see `tictactoe::engine`.
+fn main() {
+    if let Err(error) = Engine::new().run(&mut stdin().lock(), &mut stdout()) {
+        eprintln!("engine stopped: {}", error);
+        exit(1);
+    }
+}
@@ -0,0 +1,51 @@
+use std::env;
+use std::process::exit;
+
+use tictactoe::board::{Board, SIDE_SIZE, WIN_LENGTH};
+use tictactoe::enumeration::enumerate;
+
+const USAGE: &str = "usage: enumerate [--size ROWSxCOLS] [--win K] [--board BOARD] [--depth N]\n\
+                     the board is given in rows like x..|.o.|... and overrides the size";
+
+struct Options {
+    board: Board,
+    depth: usize,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    print!("{}", enumerate(&options.board, options.depth));
+}
+
+/// Reads the starting board (an empty classic one by default) and the depth (the whole game by default).
+fn parse_args() -> Result<Options, String> {
+    let mut size = format!("{}x{}", SIDE_SIZE, SIDE_SIZE);
+    let mut win = None;
+    let mut position = None;
+    let mut depth = None;
+    let number = |value: &str| value.parse::<usize>().map_err(|_| format!("not a number: {}", value));
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--size" => size = value,
+            "--win" => win = Some(value),
+            "--board" => position = Some(value),
+            "--depth" => depth = Some(number(&value)?),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+
+    let board = match position {
+        Some(position) => {
+            let win = win.as_deref().map_or(Ok(WIN_LENGTH), number)?;
+            Board::parse(&position, win).map_err(|error| format!("wrong board: {}", error))?
+        }
+        None => Board::parse_size(&size, win.as_deref()).map_err(|error| error.to_string())?,
+    };
+    let depth = depth.unwrap_or(board.size());
+    Ok(Options {board, depth})
+}
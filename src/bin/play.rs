@@ -1,9 +1,136 @@
-use tictactoe::interactive::{game::Game, input::Keyboard};
-use tictactoe::render::ConsoleRender;
+use std::env;
+use std::io::{stdin, BufReader};
+use std::net::TcpStream;
+use std::process::exit;
+
+use rand::{thread_rng, Rng};
+use tictactoe::board::{Board, SIDE_SIZE};
+use tictactoe::interactive::{game::{load, Game}, lobby::visit, network::join, player::{from_name, Player}};
+use tictactoe::interactive::terminal::Terminal;
+use tictactoe::render::console;
+
+const USAGE: &str = "usage: play [--x PLAYER] [--o PLAYER] [--size ROWSxCOLS] [--win K] [--load FILE] [--tui]\n\
+                     \x20      play --connect HOST:PORT\n\
+                     \x20      play --lobby HOST:PORT\n\
+                     players: human, ai, perfect (3x3 boards only), heuristic, first, random";
+
+struct Options {
+    x: String,
+    o: String,
+    game: Game,
+    /// Play on the full screen of the terminal, moving a cursor over the board.
+    tui: bool,
+}
 
 fn main() {
-    let mut input = Keyboard;
-    let mut game = Game::new();
-    let output = ConsoleRender;
-    game.play(&mut input, &output);
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [flag, address] = args.as_slice() {
+        match flag.as_str() {
+            "--connect" => return connect(address),
+            "--lobby" => return visit_lobby(address),
+            _ => {}
+        }
+    }
+    let mut options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    if options.tui {
+        play_full_screen(options);
+        return;
+    }
+    let (mut x, mut o) = (player(&options.x, None), player(&options.o, None));
+    options.game.play(x.as_mut(), o.as_mut(), console().as_ref());
+}
+
+/// Plays the game on the full screen, where human players use the keyboard of the terminal.
+fn play_full_screen(mut options: Options) {
+    let result = Terminal::new().and_then(|terminal| {
+        let (mut x, mut o) = (player(&options.x, Some(&terminal)), player(&options.o, Some(&terminal)));
+        options.game.play(x.as_mut(), o.as_mut(), &terminal);
+        terminal.wait()
+    });
+    if let Err(error) = result {
+        eprintln!("cannot use the terminal: {}", error);
+        exit(1);
+    }
+}
+
+/// Creates a player checked by `parse_args`; humans play at the terminal if there is one.
+fn player(name: &str, terminal: Option<&Terminal>) -> Box<dyn Player> {
+    match terminal {
+        Some(terminal) if name == "human" => Box::new(terminal.input()),
+        _ => from_name(name, thread_rng().gen()).expect("player names are checked"),
+    }
+}
+
+/// Plays a game hosted by the server at the address.
+fn connect(address: &str) {
+    let result = TcpStream::connect(address).and_then(|stream| {
+        let mut server = BufReader::new(stream.try_clone()?);
+        let mut to_server = stream;
+        join(&mut server, &mut to_server, &mut stdin().lock(), console().as_ref())
+    });
+    match result {
+        Ok(Some(_)) => {}
+        Ok(None) => println!("The connection is closed before the game is over."),
+        Err(error) => {
+            eprintln!("cannot play at {}: {}", address, error);
+            exit(1);
+        }
+    }
+}
+
+/// Sends what is typed to the lobby at the address and shows its answers until the lobby disconnects.
+fn visit_lobby(address: &str) {
+    let result = TcpStream::connect(address).and_then(|stream| {
+        visit(stream, BufReader::new(stdin()), console().as_ref())
+    });
+    if let Err(error) = result {
+        eprintln!("cannot visit the lobby at {}: {}", address, error);
+        exit(1);
+    }
+}
+
+/// Reads the options; the players of a loaded game are the ones saved with it unless given explicitly.
+fn parse_args() -> Result<Options, String> {
+    let (mut x, mut o) = (None, None);
+    let mut size = format!("{}x{}", SIDE_SIZE, SIDE_SIZE);
+    let mut win = None;
+    let mut path = None;
+    let mut tui = false;
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--tui" {
+            tui = true;
+            continue;
+        }
+        let value = args.next().ok_or(format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--x" => x = Some(value),
+            "--o" => o = Some(value),
+            "--size" => size = value,
+            "--win" => win = Some(value),
+            "--load" => path = Some(value),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+
+    let mut game = match path {
+        Some(path) => load(&path).map_err(|error| format!("cannot load {}: {}", path, error))?,
+        None => Board::parse_size(&size, win.as_deref()).and_then(Game::with_board).map_err(|error| error.to_string())?,
+    };
+    let x = x.or(game.tag("X").map(String::from)).unwrap_or(String::from("human"));
+    let o = o.or(game.tag("O").map(String::from)).unwrap_or(String::from("human"));
+    game.set_tag("X", &x);
+    game.set_tag("O", &o);
+
+    for name in [&x, &o] {
+        from_name(name, 0).ok_or(format!("unknown player: {}", name))?;
+        // Searching the whole game tree of a larger board takes far too long to wait for.
+        if (name == "perfect" || name == "ai") && game.board().size() > SIDE_SIZE*SIDE_SIZE {
+            return Err(format!("the {} player only plays on boards of up to {}x{}", name, SIDE_SIZE, SIDE_SIZE));
+        }
+    }
+    Ok(Options {x, o, game, tui})
+}
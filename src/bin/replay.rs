@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+use std::io::stdin;
+use std::process::exit;
+
+use tictactoe::interactive::viewer::Viewer;
+use tictactoe::record::parse_all;
+use tictactoe::render::console;
+
+const USAGE: &str = "usage: replay FILE [--game N]";
+
+fn main() {
+    let mut viewer = open().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    viewer.run(&mut stdin().lock(), console().as_ref());
+}
+
+/// Opens the N-th game (the first one by default) of the file given on the command line.
+fn open() -> Result<Viewer, String> {
+    let mut path = None;
+    let mut game = 1;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--game" => {
+                let value = args.next().ok_or(format!("missing value for {}", arg))?;
+                game = value.parse().map_err(|_| format!("not a number: {}", value))?;
+            }
+            _ if !arg.starts_with("--") && path.is_none() => path = Some(arg),
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    let path = path.ok_or("missing the file with games")?;
+    let text = fs::read_to_string(&path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+    let mut records = parse_all(&text)?;
+    if game == 0 || game > records.len() {
+        return Err(format!("{} has {} games", path, records.len()));
+    }
+    Viewer::new(records.swap_remove(game - 1))
+}
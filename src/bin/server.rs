@@ -0,0 +1,71 @@
+use std::env;
+use std::net::TcpListener;
+use std::process::exit;
+
+use tictactoe::board::{Board, SIDE_SIZE};
+use tictactoe::interactive::game::Game;
+use tictactoe::interactive::{lobby::serve, network::host};
+
+const USAGE: &str = "usage: server [--port N] [--size ROWSxCOLS] [--win K]\n\
+                     \x20      server --lobby [--port N]\n\
+                     players connect with: play --connect HOST:PORT, or play --lobby HOST:PORT";
+
+struct Options {
+    port: u16,
+    board: Board,
+    lobby: bool,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    let listener = TcpListener::bind(("0.0.0.0", options.port)).unwrap_or_else(|error| {
+        eprintln!("cannot listen on port {}: {}", options.port, error);
+        exit(1);
+    });
+    if options.lobby {
+        println!("Serving the lobby on port {}", options.port);
+        if let Err(error) = serve(listener) {
+            eprintln!("the lobby has failed: {}", error);
+            exit(1);
+        }
+        return;
+    }
+    println!("Waiting for two players on port {}", options.port);
+
+    let mut game = Game::with_board(options.board).unwrap_or_else(|error| {
+        eprintln!("cannot start the game: {}", error);
+        exit(1);
+    });
+    if let Err(error) = host(&listener, &mut game) {
+        eprintln!("the game has failed: {}", error);
+        exit(1);
+    }
+    print!("{}", game.record());
+}
+
+/// Reads the port (7878 by default), and the size of the board unless the server is a lobby.
+fn parse_args() -> Result<Options, String> {
+    let mut port = 7878;
+    let mut lobby = false;
+    let mut size = format!("{}x{}", SIDE_SIZE, SIDE_SIZE);
+    let mut win = None;
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--lobby" {
+            lobby = true;
+            continue;
+        }
+        let value = args.next().ok_or(format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--port" => port = value.parse().map_err(|_| format!("wrong port: {}", value))?,
+            "--size" => size = value,
+            "--win" => win = Some(value),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+    let board = Board::parse_size(&size, win.as_deref()).map_err(|error| error.to_string())?;
+    Ok(Options {port, board, lobby})
+}
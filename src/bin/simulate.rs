@@ -1,12 +1,145 @@
-use tictactoe::simulator::simulate;
+use std::env;
+use std::fs::File;
+use std::io::{stdout, BufWriter, Write};
+use std::process::exit;
+
+use rand::{thread_rng, Rng};
+use tictactoe::board::Board;
+use tictactoe::record::GameRecord;
+use tictactoe::simulator::{simulate_match, simulate_parallel, SimulationReport, CSV_HEADER};
+use tictactoe::strategy::from_name;
+
+const USAGE: &str = "usage: simulate [N] [--seed N] [--x STRATEGY] [--o STRATEGY] [--records FILE] [--format FORMAT]\n\
+                     \x20      simulate [N] [--seed N] [--x STRATEGY] [--o STRATEGY] --threads N\n\
+                     strategies: random, first, heuristic, perfect\n\
+                     formats: text (statistics, the default), json (an object per line), csv";
+
+/// What is written to the output.
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    /// The statistics of all games.
+    Text,
+    /// A JSON object per game, one per line.
+    Json,
+    /// A CSV line per game after the header.
+    Csv,
+}
+
+struct Options {
+    games: usize,
+    seed: u64,
+    x: String,
+    o: String,
+    records: Option<String>,
+    format: Format,
+    /// The number of threads to play on; more than one gives the statistics only.
+    threads: usize,
+}
 
 fn main() {
-    let boards = simulate(10, false);
-    for board in boards.iter() {
-        if board.impossible() {
-            println!("Impossible board state encountered!");
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    if let Err(error) = run(&options) {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let strategy = |name: &str, seed| from_name(name, seed).ok_or(format!("unknown strategy: {}", name));
+    let mut x = strategy(&options.x, options.seed)?;
+    let mut o = strategy(&options.o, options.seed.wrapping_add(1))?;
+    let mut out = BufWriter::new(stdout().lock());
+    let failed = |error: std::io::Error| format!("cannot write the output: {}", error);
+    if options.threads > 1 {
+        // The strategies are checked above, so they are created the same way for every thread.
+        let report = simulate_parallel(options.games, options.threads, options.seed, |seed| {
+            (strategy(&options.x, seed).unwrap(), strategy(&options.o, seed.wrapping_add(1)).unwrap())
+        });
+        write!(out, "Seed: {}, threads: {}\n{}", options.seed, options.threads, report).map_err(failed)?;
+        return out.flush().map_err(failed);
+    }
+    let mut records = match &options.records {
+        Some(path) => Some(BufWriter::new(File::create(path).map_err(|error| format!("cannot write {}: {}", path, error))?)),
+        None => None,
+    };
+
+    // The seed is kept out of the way of structured output, which is meant to be read by programs.
+    match options.format {
+        Format::Text => writeln!(out, "Seed: {}", options.seed).map_err(failed)?,
+        Format::Json => eprintln!("Seed: {}", options.seed),
+        Format::Csv => {
+            eprintln!("Seed: {}", options.seed);
+            writeln!(out, "{}", CSV_HEADER).map_err(failed)?;
+        }
+    }
+    let mut report = SimulationReport::new();
+    for round in 1..=options.games {
+        let game = simulate_match(Board::new(), x.as_mut(), o.as_mut(), false);
+        match options.format {
+            Format::Text => {}
+            Format::Json => writeln!(out, "{}", game.json(round)).map_err(failed)?,
+            Format::Csv => writeln!(out, "{}", game.csv(round)).map_err(failed)?,
+        }
+        if let Some(records) = records.as_mut() {
+            let mut record = GameRecord::new(&game.board, &game.moves);
+            record.set_tag("Event", "Simulation");
+            record.set_tag("Round", &round.to_string());
+            record.set_tag("X", &options.x);
+            record.set_tag("O", &options.o);
+            writeln!(records, "{}", record).map_err(|error| error.to_string())?;
         }
-        println!("Status: {:?}", board.status());
-        println!("{}", board);
+        report.add(&game);
+    }
+    if options.format == Format::Text {
+        write!(out, "{}", report).map_err(failed)?;
+    }
+    out.flush().map_err(failed)
+}
+
+/// Reads the number of games (10 by default), the seed given with `--seed` (random by default),
+/// the strategies of the players (random by default), the file to write the games to, the format
+/// of the output, and the number of threads.
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        games: 10,
+        seed: thread_rng().gen(),
+        x: String::from("random"),
+        o: String::from("random"),
+        records: None,
+        format: Format::Text,
+        threads: 1,
+    };
+    let mut args = env::args().skip(1);
+    let number = |value: String| value.parse().map_err(|_| format!("not a number: {}", value));
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            options.games = number(arg)? as usize;
+            continue;
+        }
+        let value = args.next().ok_or(format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--seed" => options.seed = number(value)?,
+            "--x" => options.x = value,
+            "--o" => options.o = value,
+            "--records" => options.records = Some(value),
+            "--format" => options.format = match value.as_str() {
+                "text" => Format::Text,
+                "json" => Format::Json,
+                "csv" => Format::Csv,
+                _ => return Err(format!("unknown format: {}", value)),
+            },
+            "--threads" => options.threads = number(value)? as usize,
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    if options.threads == 0 {
+        return Err(String::from("at least one thread is needed"));
+    }
+    if options.threads > 1 && (options.format != Format::Text || options.records.is_some()) {
+        return Err(String::from("games played on many threads are not written down, only their statistics"));
     }
-}
\ No newline at end of file
+    Ok(options)
+}
@@ -0,0 +1,61 @@
+use std::env;
+use std::process::exit;
+
+use rand::{thread_rng, Rng};
+use tictactoe::board::Board;
+use tictactoe::strategy::from_name;
+use tictactoe::tournament::{round_robin, Participant};
+
+const USAGE: &str = "usage: tournament [N] [--seed N] [--strategies NAME,NAME,...]\n\
+                     strategies: random, first, heuristic, perfect";
+
+struct Options {
+    games: usize,
+    seed: u64,
+    strategies: Vec<String>,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(1);
+    });
+    let mut participants = vec![];
+    for (i, name) in options.strategies.iter().enumerate() {
+        match from_name(name, options.seed.wrapping_add(i as u64)) {
+            Some(strategy) => participants.push(Participant::new(name, strategy)),
+            None => {
+                eprintln!("unknown strategy: {}\n{}", name, USAGE);
+                exit(1);
+            }
+        }
+    }
+    println!("Seed: {}, games per pair: {}\n", options.seed, options.games);
+    print!("{}", round_robin(&mut participants, options.games, &Board::new()));
+}
+
+/// Reads the number of games per pair (10 by default), the seed, and the strategies (all by default).
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        games: 10,
+        seed: thread_rng().gen(),
+        strategies: ["random", "first", "heuristic", "perfect"].map(String::from).to_vec(),
+    };
+    let mut args = env::args().skip(1);
+    let number = |value: String| value.parse().map_err(|_| format!("not a number: {}", value));
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or(format!("missing value for {}", arg))?;
+                options.seed = number(value)?;
+            }
+            "--strategies" => {
+                let value = args.next().ok_or(format!("missing value for {}", arg))?;
+                options.strategies = value.split(',').map(String::from).collect();
+            }
+            _ if !arg.starts_with("--") => options.games = number(arg)? as usize,
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    Ok(options)
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::ops::Index;
+use std::sync::{Arc, OnceLock};
+
+use crate::board::{valid_size, Board, BoardError, BoardIterator, Mark, Position, Status, SIDE_SIZE, WIN_LENGTH};
+
+/// The largest number of cells a bitboard can hold.
+pub const MAX_CELLS: usize = u128::BITS as usize;
+
+/// A board that keeps one bitmask of occupied cells per player.
+///
+/// Has the same rules as `Board` but checks the status with a handful of bitwise operations against
+/// precomputed masks of all winning lines. The cell at `(i, j)` is stored in the bit `i*cols + j`.
+#[derive(Clone)]
+pub struct BitBoard {
+    first: u128,
+    second: u128,
+    rows: usize,
+    cols: usize,
+    win_length: usize,
+    lines: Arc<[u128]>,
+}
+
+impl BitBoard {
+    /// Creates the classic 3x3 board; its winning lines are computed only once.
+    pub fn new() -> Self {
+        static CLASSIC: OnceLock<Arc<[u128]>> = OnceLock::new();
+        let lines = CLASSIC.get_or_init(|| winning_lines(SIDE_SIZE, SIDE_SIZE, WIN_LENGTH));
+        Self::with_lines(SIDE_SIZE, SIDE_SIZE, WIN_LENGTH, lines.clone())
+    }
+
+    /// Creates an empty board of the given size.
+    ///
+    /// Panics if the size is not valid for `Board::with_size` or the board has more than `MAX_CELLS` cells.
+    pub fn with_size(rows: usize, cols: usize, win_length: usize) -> Self {
+        if !valid_size(rows, cols, win_length) || rows.checked_mul(cols).is_none_or(|cells| cells > MAX_CELLS) {
+            panic!("wrong bitboard size: {}x{} with {} in a row", rows, cols, win_length);
+        }
+        Self::with_lines(rows, cols, win_length, winning_lines(rows, cols, win_length))
+    }
+
+    fn with_lines(rows: usize, cols: usize, win_length: usize, lines: Arc<[u128]>) -> Self {
+        Self {first: 0, second: 0, rows, cols, win_length, lines}
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn cols(&self) -> usize { self.cols }
+
+    pub fn win_length(&self) -> usize { self.win_length }
+
+    pub fn size(&self) -> usize { self.rows*self.cols }
+
+    pub fn contains(&self, pos: &Position) -> bool { pos.0 < self.rows && pos.1 < self.cols }
+
+    pub fn status(&self) -> Status {
+        if self.impossible() {
+            return Status::Impossible;
+        }
+
+        for &line in self.lines.iter() {
+            if self.first & line == line {
+                return Status::Winner(true);
+            }
+            if self.second & line == line {
+                return Status::Winner(false);
+            }
+        }
+
+        if (self.first | self.second).count_ones() as usize == self.size() {
+            Status::Tie
+        } else {
+            Status::Ongoing
+        }
+    }
+
+    pub fn empty(&self, pos: &Position) -> bool { (self.first | self.second) & self.bit(*pos) == 0 }
+
+    pub fn impossible(&self) -> bool {
+        let (fst, snd) = (self.first.count_ones() as i32, self.second.count_ones() as i32);
+        (fst - snd).abs() > 1
+    }
+
+    /// Returns `true` if the first player (X) makes the next turn, see `Board::first_to_move`.
+    pub fn first_to_move(&self) -> bool { self.first.count_ones() <= self.second.count_ones() }
+
+    /// Puts the mark into the cell, replacing whatever was there before.
+    pub fn set(&mut self, pos: Position, mark: Mark) {
+        let bit = self.bit(pos);
+        self.first &= !bit;
+        self.second &= !bit;
+        match mark {
+            Mark::First => self.first |= bit,
+            Mark::Second => self.second |= bit,
+            Mark::Empty => {}
+        }
+    }
+
+    fn bit(&self, pos: Position) -> u128 {
+        if !self.contains(&pos) {
+            panic!("wrong board index: {:#?}", pos);
+        }
+        1 << (pos.0*self.cols + pos.1)
+    }
+}
+
+impl Default for BitBoard {
+    fn default() -> Self { Self::new() }
+}
+
+impl TryFrom<&Board> for BitBoard {
+    type Error = BoardError;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        if board.size() > MAX_CELLS {
+            return Err(BoardError::TooManyCells {cells: board.size(), max: MAX_CELLS});
+        }
+        let mut bitboard = if (board.rows(), board.cols(), board.win_length()) == (SIDE_SIZE, SIDE_SIZE, WIN_LENGTH) {
+            BitBoard::new()
+        } else {
+            BitBoard::with_size(board.rows(), board.cols(), board.win_length())
+        };
+        for (pos, mark) in BoardIterator::new(board) {
+            bitboard.set(pos, mark);
+        }
+        Ok(bitboard)
+    }
+}
+
+impl From<&BitBoard> for Board {
+    fn from(bitboard: &BitBoard) -> Self {
+        let mut board = Board::with_size(bitboard.rows, bitboard.cols, bitboard.win_length);
+        for i in 0..bitboard.rows {
+            for j in 0..bitboard.cols {
+                board.set((i, j), bitboard[(i, j)]);
+            }
+        }
+        board
+    }
+}
+
+impl TryFrom<&str> for BitBoard {
+    type Error = BoardError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        BitBoard::try_from(&Board::try_from(value)?)
+    }
+}
+
+impl fmt::Display for BitBoard {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", Board::from(self))
+    }
+}
+
+impl Index<Position> for BitBoard {
+    type Output = Mark;
+    fn index(&self, pos: Position) -> &Self::Output {
+        let bit = self.bit(pos);
+        if self.first & bit != 0 {
+            &Mark::First
+        } else if self.second & bit != 0 {
+            &Mark::Second
+        } else {
+            &Mark::Empty
+        }
+    }
+}
+
+/// Computes the masks of all lines of `win_length` cells: rows, columns, and both diagonals.
+fn winning_lines(rows: usize, cols: usize, win_length: usize) -> Arc<[u128]> {
+    let mut lines = vec![];
+    for i in 0..rows {
+        for j in 0..cols {
+            for (di, dj) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+                let end_i = i as isize + di*(win_length as isize - 1);
+                let end_j = j as isize + dj*(win_length as isize - 1);
+                if end_i >= rows as isize || end_j < 0 || end_j >= cols as isize {
+                    continue;
+                }
+                let line = (0..win_length as isize).fold(0u128, |mask, step| {
+                    let (ci, cj) = (i as isize + step*di, j as isize + step*dj);
+                    mask | 1 << (ci as usize*cols + cj as usize)
+                });
+                lines.push(line);
+            }
+        }
+    }
+    lines.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+    use crate::simulator::simulate;
+
+    #[test]
+    fn test_classic_winning_lines() {
+        assert_eq!(BitBoard::new().lines.len(), 8);
+        assert_eq!(winning_lines(4, 4, 3).len(), 24);
+    }
+
+    #[test]
+    fn test_set_and_index() {
+        let mut board = BitBoard::new();
+
+        board.set((0, 0), Mark::First);
+        board.set((1, 1), Mark::Second);
+        board.set((2, 2), Mark::First);
+        board.set((2, 2), Mark::Second);
+
+        assert_eq!(board[(0, 0)], Mark::First);
+        assert_eq!(board[(1, 1)], Mark::Second);
+        assert_eq!(board[(2, 2)], Mark::Second);
+        assert!(board.empty(&(0, 1)));
+        assert!(!board.empty(&(1, 1)));
+        assert_eq!(format!("{}", board),
+                   "+---+\n\
+                    |x..|\n\
+                    |.o.|\n\
+                    |..o|\n\
+                    +---+\n");
+    }
+
+    #[test]
+    fn test_status() {
+        let status = |s| BitBoard::try_from(s).unwrap().status();
+
+        assert_eq!(status("xxx|oo.|..."), Status::Winner(true));
+        assert_eq!(status("xx.|ooo|x.."), Status::Winner(false));
+        assert_eq!(status("xox|xoo|oxx"), Status::Tie);
+        assert_eq!(status("x..|.o.|..."), Status::Ongoing);
+        assert_eq!(status("xxx|xxx|xxx"), Status::Impossible);
+        assert_eq!(status("...x|..x.|.x..|oo.."), Status::Winner(true));
+    }
+
+    #[test]
+    fn test_same_status_as_board() {
+        for game in simulate(100, &mut thread_rng(), false) {
+            let board = game.board;
+            let bitboard = BitBoard::try_from(&board).unwrap();
+
+            assert_eq!(bitboard.status(), board.status());
+            assert_eq!(bitboard.first_to_move(), board.first_to_move());
+            assert_eq!(format!("{}", bitboard), format!("{}", board));
+        }
+    }
+
+    #[test]
+    fn test_too_many_cells_to_parse() {
+        let row = ".".repeat(12);
+        let board = [row.as_str(); 12].join("|");
+
+        assert_eq!(BitBoard::try_from(board.as_str()).err(), Some(BoardError::TooManyCells {cells: 144, max: 128}));
+        assert_eq!(BitBoard::try_from(&Board::with_size(12, 12, 5)).err(), Some(BoardError::TooManyCells {cells: 144, max: 128}));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_large_board() {
+        BitBoard::with_size(12, 12, 5);
+    }
+}
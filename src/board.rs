@@ -1,9 +1,14 @@
 use std::fmt;
-use std::collections::HashMap;
-use std::ops::{Index, IndexMut};
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
 
+/// The side of the classic board created by `Board::new`.
 pub const SIDE_SIZE: usize = 3;
-pub const BOARD_SIZE: usize = SIDE_SIZE * SIDE_SIZE;
+/// The number of marks in a row needed to win unless specified otherwise.
+pub const WIN_LENGTH: usize = 3;
+
+/// Steps from one cell of a line to the next one: rows, columns, and both diagonals.
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
 
 pub type Position = (usize, usize);
 
@@ -14,6 +19,13 @@ pub enum Mark {
     Empty
 }
 
+impl Mark {
+    /// The mark of the first (X) or the second (O) player.
+    pub fn of(first: bool) -> Self {
+        if first { Mark::First } else { Mark::Second }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Status {
     Winner(bool),
@@ -22,127 +34,346 @@ pub enum Status {
     Impossible,
 }
 
-#[derive(Copy, Clone)]
-pub struct Board([Mark; BOARD_SIZE]);
+/// The reason a board could not be parsed; rows and columns are counted from zero.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoardError {
+    /// A row has a different number of cells than the first one.
+    WrongRowLength { row: usize, expected: usize, found: usize },
+    /// A cell is neither `x`, `o`, nor `.`.
+    BadChar { row: usize, col: usize, char: char },
+    /// The win length does not fit the board, see `Board::with_size`.
+    WrongSize { rows: usize, cols: usize, win_length: usize },
+    /// The board has more cells than a bitboard can hold.
+    TooManyCells { cells: usize, max: usize },
+    /// The size is not written as `ROWSxCOLS`.
+    BadSize(String),
+    /// A number of rows, columns, or marks in a row is not a number.
+    NotANumber(String),
+    /// The number of cells does not fit in a `usize`.
+    TooLarge { rows: usize, cols: usize },
+    /// A game cannot start on the board because it already has marks.
+    NotEmpty(String),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::WrongRowLength {row, expected, found} =>
+                write!(formatter, "row {} has {} cells instead of {}", row, found, expected),
+            BoardError::BadChar {row, col, char} =>
+                write!(formatter, "unexpected '{}' at row {}, column {}", char, row, col),
+            BoardError::WrongSize {rows, cols, win_length} =>
+                write!(formatter, "cannot make {} in a row on a {}x{} board", win_length, rows, cols),
+            BoardError::TooManyCells {cells, max} =>
+                write!(formatter, "the board has {} cells, more than {}", cells, max),
+            BoardError::BadSize(size) => write!(formatter, "wrong board size: {}", size),
+            BoardError::NotANumber(value) => write!(formatter, "not a number: {}", value),
+            BoardError::TooLarge {rows, cols} => write!(formatter, "a {}x{} board is too large", rows, cols),
+            BoardError::NotEmpty(notation) => write!(formatter, "games start on an empty board: {}", notation),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+/// A board of `rows` by `cols` cells where a player needs `win_length` marks in a row to win.
+///
+/// Keeps a Zobrist hash of its marks that is updated with every mark placed or removed, so equal
+/// boards hash the same without looking at their cells.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Board {
+    cells: Vec<Mark>,
+    rows: usize,
+    cols: usize,
+    win_length: usize,
+    hash: u64,
+}
 
 impl Board {
-    pub fn new() -> Self { Self {0: [Mark::Empty; BOARD_SIZE]} } 
+    /// Creates the classic 3x3 board with a three-in-a-row win condition.
+    pub fn new() -> Self { Self::with_size(SIDE_SIZE, SIDE_SIZE, WIN_LENGTH) }
+
+    /// Creates an empty board of the given size.
+    ///
+    /// Panics unless `1 <= win_length <= min(rows, cols)`, so a line can be completed in any direction,
+    /// and if the number of cells does not fit in a `usize`.
+    pub fn with_size(rows: usize, cols: usize, win_length: usize) -> Self {
+        if !valid_size(rows, cols, win_length) {
+            panic!("wrong board size: {}x{} with {} in a row", rows, cols, win_length);
+        }
+        let cells = rows.checked_mul(cols).unwrap_or_else(|| panic!("a {}x{} board is too large", rows, cols));
+        Self {cells: vec![Mark::Empty; cells], rows, cols, win_length, hash: 0}
+    }
+
+    /// Creates an empty board from its size written like `4x5` and the number of marks in a row to
+    /// win, as players give them. Without the number, it is `WIN_LENGTH` or the shorter side of a
+    /// smaller board.
+    pub fn parse_size(size: &str, win_length: Option<&str>) -> Result<Self, BoardError> {
+        let (rows, cols) = parse_dimensions(size)?;
+        let win_length = win_length.map_or(Ok(WIN_LENGTH.min(rows).min(cols)), parse_number)?;
+        if !valid_size(rows, cols, win_length) {
+            return Err(BoardError::WrongSize {rows, cols, win_length});
+        }
+        if rows.checked_mul(cols).is_none() {
+            return Err(BoardError::TooLarge {rows, cols});
+        }
+        Ok(Self::with_size(rows, cols, win_length))
+    }
+
+    /// Parses a board from rows of `x`, `o`, and `.` separated with `|`, like `x..|.o.|...`.
+    pub fn parse(value: &str, win_length: usize) -> Result<Self, BoardError> {
+        let lines: Vec<&str> = value.split('|').collect();
+        let cols = lines[0].chars().count();
+        let mut cells = Vec::with_capacity(lines.len()*cols);
+        for (line_no, line) in lines.iter().enumerate() {
+            let found = line.chars().count();
+            if found != cols {
+                return Err(BoardError::WrongRowLength {row: line_no, expected: cols, found});
+            }
+            for (i, char) in line.chars().enumerate() {
+                cells.push(match char {
+                    'x' => Mark::First,
+                    'o' => Mark::Second,
+                    '.' => Mark::Empty,
+                    _   => return Err(BoardError::BadChar {row: line_no, col: i, char}),
+                });
+            }
+        }
+        if !valid_size(lines.len(), cols, win_length) {
+            return Err(BoardError::WrongSize {rows: lines.len(), cols, win_length});
+        }
+        let hash = cells.iter().enumerate().fold(0, |hash, (i, mark)| hash ^ zobrist_key(i, *mark));
+        Ok(Self {cells, rows: lines.len(), cols, win_length, hash})
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn cols(&self) -> usize { self.cols }
+
+    pub fn win_length(&self) -> usize { self.win_length }
+
+    /// The total number of cells on the board.
+    pub fn size(&self) -> usize { self.cells.len() }
+
+    pub fn contains(&self, pos: &Position) -> bool { pos.0 < self.rows && pos.1 < self.cols }
+
+    /// Puts the mark into the cell, replacing whatever was there before.
+    ///
+    /// This is the only way to change a cell: boards used to implement `IndexMut`, but writing
+    /// `board[pos] = mark` through a reference cannot update the Zobrist hash along with the cell.
+    pub fn set(&mut self, pos: Position, mark: Mark) {
+        let (i, j) = self.check_bounds(pos);
+        let index = i*self.cols + j;
+        self.hash ^= zobrist_key(index, self.cells[index]) ^ zobrist_key(index, mark);
+        self.cells[index] = mark;
+    }
+
+    /// The Zobrist hash of the marks on the board.
+    pub fn zobrist(&self) -> u64 { self.hash }
 
     pub fn status(&self) -> Status {
         if self.impossible() {
             return Status::Impossible;
         }
-        
-        for i in 0..SIDE_SIZE {
-            for rows in [true, false] {
-                if let status @ Status::Winner(_) = self.check(i, rows) {
-                    return status
-                }
-            }
-        }
 
-        if let status @ Status::Winner(_) = self.diagonals() {
-            return status;
+        if let Some((start, _)) = self.find_line() {
+            return Status::Winner(self[start] == Mark::First);
         }
-        
-        if self.0.iter().any(|x| *x == Mark::Empty) {
+
+        if self.cells.contains(&Mark::Empty) {
             Status::Ongoing
         } else {
             Status::Tie
         }
     }
 
+    /// Returns the cells of the line that won the game, or `None` unless there is a winner.
+    pub fn winning_line(&self) -> Option<Vec<Position>> {
+        if self.impossible() {
+            return None;
+        }
+        let ((i, j), (di, dj)) = self.find_line()?;
+        Some((0..self.win_length as isize)
+            .map(|step| ((i as isize + step*di) as usize, (j as isize + step*dj) as usize))
+            .collect())
+    }
+
     pub fn empty(&self, pos: &Position) -> bool { self[*pos] == Mark::Empty }
 
     pub fn impossible(&self) -> bool {
-        let (fst, snd) = self.0.iter().fold((0i8, 0i8), |acc, val| {
+        let (fst, snd) = self.counts();
+        (fst - snd).abs() > 1
+    }
+
+    /// Returns `true` if the first player (X) makes the next turn.
+    ///
+    /// The first player always opens the game, so it is their turn whenever
+    /// they don't have more marks on the board than their opponent.
+    pub fn first_to_move(&self) -> bool {
+        let (fst, snd) = self.counts();
+        fst <= snd
+    }
+
+    /// Writes the board in the form accepted by `Board::parse`, like `x..|.o.|...`.
+    pub fn notation(&self) -> String {
+        let rows: Vec<String> = (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| char_of(self[(i, j)])).collect())
+            .collect();
+        rows.join("|")
+    }
+
+    /// Turns the board a quarter clockwise; a board of `rows` by `cols` becomes `cols` by `rows`.
+    pub fn rotate(&self) -> Self {
+        let mut rotated = Board::with_size(self.cols, self.rows, self.win_length);
+        for (pos, mark) in BoardIterator::new(self) {
+            rotated.set((pos.1, self.rows - 1 - pos.0), mark);
+        }
+        rotated
+    }
+
+    /// Mirrors the board from left to right.
+    pub fn reflect(&self) -> Self {
+        let mut reflected = Board::with_size(self.rows, self.cols, self.win_length);
+        for (pos, mark) in BoardIterator::new(self) {
+            reflected.set((pos.0, self.cols - 1 - pos.1), mark);
+        }
+        reflected
+    }
+
+    /// All eight rotations and reflections of the board with the transforms that produce them.
+    ///
+    /// Symmetric boards produce some of the boards more than once.
+    pub fn symmetries(&self) -> Vec<(Board, Transform)> {
+        Transform::all().map(|transform| (transform.apply(self), transform)).to_vec()
+    }
+
+    /// The symmetry of the board with the lexicographically smallest notation, and the transform
+    /// that turns the board into it. Boards that are symmetries of each other have the same canonical form.
+    pub fn canonical(&self) -> (Board, Transform) {
+        self.symmetries().into_iter()
+            .min_by_key(|(board, _)| board.notation())
+            .expect("there is always the identity")
+    }
+
+    fn counts(&self) -> (isize, isize) {
+        self.cells.iter().fold((0, 0), |acc, val| {
             let (i, j) = match val {
                 Mark::First => (1, 0),
                 Mark::Second => (0, 1),
                 _ => (0, 0),
             };
             (acc.0 + i, acc.1 + j)
-        });
-        (fst - snd).abs() > 1
+        })
     }
 
-    fn check(&self, i: usize, rows: bool) -> Status {
-        let mut count: HashMap<Mark, usize> = HashMap::new();
-        for j in 0..3 {
-            let pos = if rows { (i, j) } else { (j, i) };
-            let val = self[pos];
-            count.entry(val).and_modify(|c| *c += 1).or_insert(1);
-        }
-        if let Some(3) = count.get(&Mark::First) {
-            return Status::Winner(true);
-        }
-        if let Some(3) = count.get(&Mark::Second) {
-            return Status::Winner(false);
+    /// Finds the first line of the same marks: the cell where it starts and its direction.
+    fn find_line(&self) -> Option<(Position, (isize, isize))> {
+        for pos in (0..self.size()).map(|i| (i / self.cols, i % self.cols)) {
+            for direction in DIRECTIONS {
+                if self.check(pos, direction) {
+                    return Some((pos, direction));
+                }
+            }
         }
-        Status::Ongoing
+        None
     }
 
-    fn diagonals(&self) -> Status {
-        for diagonal in vec![
-            vec![(0, 0), (1, 1), (2, 2)],
-            vec![(2, 0), (1, 1), (0, 2)],
-        ] {
-            let mut count: HashMap<Mark, usize> = HashMap::new();
-            for pos in diagonal {
-                count.entry(self[pos]).and_modify(|c| *c += 1).or_insert(1);
-            }
-            if let Some(3) = count.get(&Mark::First) {
-                return Status::Winner(true);
+    /// Checks if the line of `win_length` cells going from `start` in `direction` has the same marks.
+    fn check(&self, start: Position, direction: (isize, isize)) -> bool {
+        let first = self[start];
+        if first == Mark::Empty {
+            return false;
+        }
+        for step in 1..self.win_length as isize {
+            let i = start.0 as isize + step*direction.0;
+            let j = start.1 as isize + step*direction.1;
+            if i < 0 || j < 0 || !self.contains(&(i as usize, j as usize)) {
+                return false;
             }
-            if let Some(3) = count.get(&Mark::Second) {
-                return Status::Winner(false);
+            if self[(i as usize, j as usize)] != first {
+                return false;
             }
         }
-        Status::Ongoing
+        true
+    }
+
+    fn check_bounds(&self, pos: Position) -> Position {
+        if !self.contains(&pos) {
+            panic!("wrong board index: {:#?}", pos);
+        }
+        pos
     }
 }
 
+/// One of the eight symmetries of a board: an optional reflection followed by quarter turns clockwise.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Transform {
+    pub reflected: bool,
+    pub rotations: usize,
+}
+
+impl Transform {
+    /// The identity and the other seven symmetries: quarter turns first, then the same turns after a reflection.
+    pub fn all() -> [Transform; 8] {
+        std::array::from_fn(|i| Transform {reflected: i >= 4, rotations: i % 4})
+    }
+
+    pub fn apply(&self, board: &Board) -> Board {
+        let mut board = if self.reflected { board.reflect() } else { board.clone() };
+        for _ in 0..self.rotations % 4 {
+            board = board.rotate();
+        }
+        board
+    }
+
+    /// Finds where a cell of a board of the given size ends up after the transform.
+    pub fn position(&self, pos: Position, rows: usize, cols: usize) -> Position {
+        let (mut pos, mut rows, mut cols) = (pos, rows, cols);
+        if self.reflected {
+            pos = (pos.0, cols - 1 - pos.1);
+        }
+        for _ in 0..self.rotations % 4 {
+            pos = (pos.1, rows - 1 - pos.0);
+            (rows, cols) = (cols, rows);
+        }
+        pos
+    }
+
+    /// The transform that undoes this one.
+    pub fn inverse(&self) -> Transform {
+        if self.reflected {
+            *self
+        } else {
+            Transform {reflected: false, rotations: (4 - self.rotations % 4) % 4}
+        }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self { Self::new() }
+}
+
 impl TryFrom<&str> for Board {
-    type Error = &'static str;
+    type Error = BoardError;
     
+    /// Parses a board of any size with the default win length, see `Board::parse`.
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut board = Board::new();
-        let mut iter = value.split('|').take(SIDE_SIZE);
-        let mut line_no = 0;
-        while let Some(line) = iter.next() {
-            if line.len() != SIDE_SIZE {
-                return Err("failed");
-            }
-            for (i, char) in line.chars().enumerate() {
-                board[(line_no, i)] = match char {
-                    'x' => Mark::First,
-                    'o' => Mark::Second,
-                    '.' => Mark::Empty,
-                    _   => return Err("failed"),
-                };
-            }
-            line_no += 1;
-        }
-        Ok(board)
+        Board::parse(value, WIN_LENGTH)
     }
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("+---+\n")?;
-        for i in 0..=2 {
+        let border = format!("+{}+\n", "-".repeat(self.cols));
+        formatter.write_str(&border)?;
+        for i in 0..self.rows {
             formatter.write_str("|")?;
-            for j in 0..=2 {
-                let char = match self[(i, j)] {
-                    Mark::Empty => '.',
-                    Mark::First => 'x',
-                    Mark::Second => 'o',
-                };
-                write!(formatter, "{}", char)?;
+            for j in 0..self.cols {
+                write!(formatter, "{}", char_of(self[(i, j)]))?;
             }
-            write!(formatter, "|\n")?;
+            writeln!(formatter, "|")?;
         }
-        formatter.write_str("+---+\n")?;
+        formatter.write_str(&border)?;
         Ok(())
     }
 }
@@ -150,15 +381,14 @@ impl fmt::Display for Board {
 impl Index<Position> for Board {
     type Output = Mark;
     fn index(&self, pos: Position) -> &Self::Output {
-        let (i, j) = check_bounds(pos);
-        &self.0[i*SIDE_SIZE + j]
+        let (i, j) = self.check_bounds(pos);
+        &self.cells[i*self.cols + j]
     }
 }
 
-impl IndexMut<Position> for Board {
-    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
-        let (i, j) = check_bounds(pos);
-        &mut self.0[i*SIDE_SIZE + j]
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
     }
 }
 
@@ -175,10 +405,10 @@ impl<'a> Iterator for BoardIterator<'a> {
     type Item = (Position, Mark);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= BOARD_SIZE {
+        if self.current >= self.board.size() {
             None
         } else {
-            let pos = (self.current / SIDE_SIZE, self.current % SIDE_SIZE);
+            let pos = (self.current / self.board.cols, self.current % self.board.cols);
             let mark = self.board[pos];
             self.current += 1;
             Some((pos, mark))
@@ -186,11 +416,44 @@ impl<'a> Iterator for BoardIterator<'a> {
     }
 }
 
-fn check_bounds(pos: Position) -> Position {
-    if pos.0 >= SIDE_SIZE || pos.1 >= SIDE_SIZE { 
-        panic!("wrong board index: {:#?}", pos);
+/// Reads the numbers of rows and columns from a size written like `4x5`, without creating the board.
+pub fn parse_dimensions(size: &str) -> Result<(usize, usize), BoardError> {
+    let (rows, cols) = size.split_once('x').ok_or(BoardError::BadSize(String::from(size)))?;
+    Ok((parse_number(rows)?, parse_number(cols)?))
+}
+
+fn parse_number(value: &str) -> Result<usize, BoardError> {
+    value.parse().map_err(|_| BoardError::NotANumber(String::from(value)))
+}
+
+/// Checks if a board of the given size can be created, see `Board::with_size`.
+pub fn valid_size(rows: usize, cols: usize, win_length: usize) -> bool {
+    win_length >= 1 && win_length <= rows.min(cols)
+}
+
+/// The random number that a mark in the cell contributes to the Zobrist hash; empty cells contribute nothing.
+///
+/// The numbers come from the SplitMix64 generator seeded with the cell and the mark, so boards of any
+/// size get them without a precomputed table.
+fn zobrist_key(index: usize, mark: Mark) -> u64 {
+    let side = match mark {
+        Mark::Empty => return 0,
+        Mark::First => 0,
+        Mark::Second => 1,
+    };
+    let mut z = (2*index as u64 + side + 1).wrapping_mul(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The character of a mark in the board's notation.
+pub(crate) fn char_of(mark: Mark) -> char {
+    match mark {
+        Mark::Empty => '.',
+        Mark::First => 'x',
+        Mark::Second => 'o',
     }
-    pos
 }
 
 pub fn player_name(first: bool) -> String {
@@ -200,6 +463,7 @@ pub fn player_name(first: bool) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_empty_board() {
@@ -234,9 +498,9 @@ mod tests {
     fn test_board_manually_set() {
         let mut board = Board::new();
 
-        board[(0, 0)] = Mark::First;
-        board[(1, 1)] = Mark::Second;
-        board[(2, 2)] = Mark::First;
+        board.set((0, 0), Mark::First);
+        board.set((1, 1), Mark::Second);
+        board.set((2, 2), Mark::First);
 
         assert_eq!(format!("{}", board),
                   "+---+\n\
@@ -248,7 +512,7 @@ mod tests {
 
     #[test]
     fn test_board_fails_to_create_from_invalid_strings() {
-        let strings = vec!["......", "x|x|x", "...|xyz|...", "xxx...ooo"];
+        let strings = ["......", "x|x|x", "...|xyz|...", "xxx...ooo"];
 
         let mut results = strings.iter().map(|x| Board::try_from(*x));
 
@@ -262,12 +526,118 @@ mod tests {
         assert_eq!(board.status(), Status::Impossible);
     }
 
+    #[test]
+    fn test_rectangular_board_from_string() {
+        let board = Board::try_from("....|.xo.|....").unwrap();
+
+        assert_eq!(board.rows(), 3);
+        assert_eq!(board.cols(), 4);
+        assert_eq!(board[(1, 1)], Mark::First);
+        assert_eq!(board[(1, 2)], Mark::Second);
+        assert_eq!(format!("{}", board),
+                   "+----+\n\
+                    |....|\n\
+                    |.xo.|\n\
+                    |....|\n\
+                    +----+\n");
+    }
+
+    #[test]
+    fn test_board_fails_with_ragged_rows() {
+        assert!(Board::try_from("....|...|....").is_err());
+        assert!(Board::parse("xxxx|....", 3).is_err());
+    }
+
+    #[test]
+    fn test_board_errors_point_at_the_problem() {
+        let error = |s| Board::try_from(s).err().unwrap();
+
+        assert_eq!(error("...|..|..."), BoardError::WrongRowLength {row: 1, expected: 3, found: 2});
+        assert_eq!(error("...|xyz|..."), BoardError::BadChar {row: 1, col: 1, char: 'y'});
+        assert_eq!(error("......"), BoardError::WrongSize {rows: 1, cols: 6, win_length: 3});
+        assert_eq!(Board::parse("xxx|..", 3).err().unwrap().to_string(), "row 1 has 2 cells instead of 3");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let board = Board::parse_size("4x5", Some("4")).unwrap();
+        assert_eq!((board.rows(), board.cols(), board.win_length()), (4, 5, 4));
+        assert_eq!(Board::parse_size("3x3", None).unwrap(), Board::new());
+        assert_eq!(Board::parse_size("2x5", None).unwrap().win_length(), 2);
+
+        assert_eq!(Board::parse_size("3-3", None).err().unwrap(), BoardError::BadSize(String::from("3-3")));
+        assert_eq!(Board::parse_size("3xa", None).err().unwrap(), BoardError::NotANumber(String::from("a")));
+        assert_eq!(Board::parse_size("3x3", Some("")).err().unwrap(), BoardError::NotANumber(String::new()));
+        assert_eq!(Board::parse_size("3x3", Some("4")).err().unwrap(), BoardError::WrongSize {rows: 3, cols: 3, win_length: 4});
+        let huge = usize::MAX / 2;
+        assert_eq!(Board::parse_size(&format!("{}x{}", huge, huge), Some("1")).err().unwrap(), BoardError::TooLarge {rows: huge, cols: huge});
+    }
+
+    #[test]
+    fn test_k_in_a_row_on_larger_board() {
+        let rows = Board::parse("....|.xxx|oo..|o...", 3).unwrap();
+        let diagonal = Board::parse("o...|.o..|..o.|xx.x", 3).unwrap();
+        let anti_diagonal = Board::parse("...x|..x.|.x..|oo..", 3).unwrap();
+        let short = Board::parse("xx..|oo..|....|....", 3).unwrap();
+        let gomoku = Board::parse(".....|xxxx.|oooo.|.....|.....", 5).unwrap();
+
+        assert_eq!(rows.status(), Status::Winner(true));
+        assert_eq!(diagonal.status(), Status::Winner(false));
+        assert_eq!(anti_diagonal.status(), Status::Winner(true));
+        assert_eq!(short.status(), Status::Ongoing);
+        assert_eq!(gomoku.status(), Status::Ongoing);
+    }
+
+    #[test]
+    fn test_winning_line() {
+        let row = Board::try_from("oo.|xxx|...").unwrap();
+        let anti_diagonal = Board::try_from("xxo|.o.|ox.").unwrap();
+        let gomoku = Board::parse(".....|xxxx.|ooooo|.....|x....", 5).unwrap();
+        let ongoing = Board::try_from("x..|.o.|...").unwrap();
+
+        assert_eq!(row.winning_line(), Some(vec![(1, 0), (1, 1), (1, 2)]));
+        assert_eq!(anti_diagonal.winning_line(), Some(vec![(0, 2), (1, 1), (2, 0)]));
+        assert_eq!(gomoku.winning_line(), Some(vec![(2, 0), (2, 1), (2, 2), (2, 3), (2, 4)]));
+        assert_eq!(ongoing.winning_line(), None);
+    }
+
+    #[test]
+    fn test_tie_on_larger_board() {
+        let board = Board::parse("xxoo|ooxx|xxoo|ooxx", 3).unwrap();
+
+        assert_eq!(board.status(), Status::Tie);
+    }
+
+    #[test]
+    fn test_board_iterator_on_rectangular_board() {
+        let board = Board::with_size(2, 3, 2);
+
+        let positions: Vec<Position> = BoardIterator::new(&board).map(|(pos, _)| pos).collect();
+
+        assert_eq!(positions, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let board = Board::with_size(2, 3, 2);
+
+        let _ = board[(2, 0)];
+    }
+
+    #[test]
+    fn test_first_to_move() {
+        assert!(Board::new().first_to_move());
+        assert!(!Board::try_from("...|.x.|...").unwrap().first_to_move());
+        assert!(Board::try_from("o..|.x.|...").unwrap().first_to_move());
+    }
+
     #[test]
     fn test_board_iterator() {
         let mut board = Board::new();
-        board[(0, 0)] = Mark::First;
-        board[(1, 1)] = Mark::Second;
-        board[(2, 2)] = Mark::First;
+        board.set((0, 0), Mark::First);
+        board.set((1, 1), Mark::Second);
+        board.set((2, 2), Mark::First);
 
         let mut iter = BoardIterator::new(&board);
 
@@ -282,4 +652,111 @@ mod tests {
         assert_eq!(iter.next(), Some(((2, 2), Mark::First)));
         assert_eq!(iter.next(), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_notation() {
+        for notation in ["x..|.o.|...", "....|.xo.|...."] {
+            assert_eq!(Board::try_from(notation).unwrap().notation(), notation);
+        }
+    }
+
+    #[test]
+    fn test_rotate_and_reflect() {
+        let board = Board::try_from("xo..|....|...o").unwrap();
+
+        assert_eq!(board.rotate().notation(), "..x|..o|...|o..");
+        assert_eq!(board.reflect().notation(), "..ox|....|o...");
+        assert_eq!(board.rotate().rotate().rotate().rotate().notation(), board.notation());
+        assert_eq!(board.reflect().reflect().notation(), board.notation());
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let board = Board::try_from("xo.|...|...").unwrap();
+        let empty = Board::new();
+
+        let notations: HashSet<String> = board.symmetries().iter().map(|(b, _)| b.notation()).collect();
+        let empty_notations: HashSet<String> = empty.symmetries().iter().map(|(b, _)| b.notation()).collect();
+
+        assert_eq!(board.symmetries().len(), 8);
+        assert_eq!(notations.len(), 8);
+        assert_eq!(empty_notations.len(), 1);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let board = Board::try_from("x..|..o|...").unwrap();
+        let (canonical, transform) = board.canonical();
+
+        assert_eq!(canonical.notation(), "...|..o|x..");
+        assert_eq!(transform.apply(&board).notation(), canonical.notation());
+        assert_eq!(transform.inverse().apply(&canonical).notation(), board.notation());
+        for (symmetry, _) in board.symmetries() {
+            assert_eq!(symmetry.canonical().0.notation(), canonical.notation());
+        }
+    }
+
+    #[test]
+    fn test_transform_positions() {
+        let board = Board::try_from("xo..|....|...o").unwrap();
+
+        for transform in Transform::all() {
+            let transformed = transform.apply(&board);
+            for (pos, mark) in BoardIterator::new(&board) {
+                let moved = transform.position(pos, board.rows(), board.cols());
+                assert_eq!(transformed[moved], mark);
+                assert_eq!(transform.inverse().position(moved, transformed.rows(), transformed.cols()), pos);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reachable_positions_up_to_symmetry() {
+        let mut layer = vec![Board::new()];
+        let mut seen = HashSet::from([Board::new().notation()]);
+        while !layer.is_empty() {
+            let mut next = vec![];
+            for board in layer.iter().filter(|board| board.status() == Status::Ongoing) {
+                for (pos, _) in BoardIterator::new(board).filter(|(_, mark)| *mark == Mark::Empty) {
+                    let mut child = board.clone();
+                    child.set(pos, Mark::of(board.first_to_move()));
+                    let (canonical, _) = child.canonical();
+                    if seen.insert(canonical.notation()) {
+                        next.push(canonical);
+                    }
+                }
+            }
+            layer = next;
+        }
+
+        assert_eq!(seen.len(), 765);
+    }
+
+    #[test]
+    fn test_zobrist_hash_follows_marks() {
+        let mut board = Board::new();
+        board.set((0, 0), Mark::First);
+        board.set((1, 1), Mark::Second);
+        board.set((2, 2), Mark::First);
+        let parsed = Board::try_from("x..|.o.|..x").unwrap();
+
+        assert_eq!(board.zobrist(), parsed.zobrist());
+        assert_eq!(board, parsed);
+        board.set((2, 2), Mark::Second);
+        assert_ne!(board.zobrist(), parsed.zobrist());
+        board.set((2, 2), Mark::Empty);
+        board.set((1, 1), Mark::Empty);
+        board.set((0, 0), Mark::Empty);
+        assert_eq!(board.zobrist(), Board::new().zobrist());
+    }
+
+    #[test]
+    fn test_boards_as_keys() {
+        let boards = ["x..|...|...", "...|...|..x", "x..|...|...", "x..|...|..."];
+
+        let unique: HashSet<Board> = boards.iter().map(|b| Board::try_from(*b).unwrap()).collect();
+
+        assert_eq!(unique.len(), 2);
+        assert_ne!(Board::with_size(3, 4, 3), Board::with_size(4, 3, 3));
+    }
+}
@@ -0,0 +1,286 @@
+//! A line-oriented protocol for driving the solver from other programs, in the spirit of the
+//! Universal Chess Interface (UCI) spoken by chess engines.
+//!
+//! The client writes one command per line to the engine's input, and the engine answers on its output:
+//!
+//! ```text
+//! isready                                  answered with `readyok` once the engine can take commands
+//! newgame                                  starts over on the classic empty board and forgets cached searches
+//! position <board> [win K] [moves r,c ...] sets up the board and makes the turns in order
+//! go [movetime N]                          searches for at most N milliseconds (10 seconds by default)
+//!                                          and answers with `bestmove`
+//! quit                                     stops the engine
+//! ```
+//!
+//! The board is either `startpos` for an empty 3x3 board or its rows as accepted by
+//! `Board::parse`, like `x..|.o.|...`, with K marks in a row to win (3 by default). The player to
+//! move is the one with fewer marks on the board, X if both have the same number.
+//!
+//! `go` answers with a line of search statistics followed by the chosen turn:
+//!
+//! ```text
+//! info score draw nodes 5615 time 30
+//! bestmove 0,0
+//! ```
+//!
+//! The score is `win N` or `loss N` if the game ends after N more turns with perfect play, `draw`,
+//! or `unknown` if the search ran out of time, in which case the turn is picked by the heuristic
+//! strategy instead. `bestmove none` means the game is over. Any command the engine cannot follow
+//! is answered with `error <reason>` and leaves the position as it was.
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+
+use crate::board::{valid_size, Board, Mark, Position, Status, SIDE_SIZE, WIN_LENGTH};
+use crate::solver::{search, Outcome, TranspositionTable};
+use crate::strategy::{Heuristic, Strategy};
+
+/// How long `go` searches when the client gives no time; the game trees of boards larger than
+/// 3x3 are too big to search to the end.
+pub const DEFAULT_MOVETIME: Duration = Duration::from_secs(10);
+
+/// The longest search when the time given is too long to count from now.
+const MAX_MOVETIME: Duration = Duration::from_secs(24*60*60);
+
+#[derive(Debug, PartialEq)]
+pub enum EngineCommand {
+    IsReady,
+    NewGame,
+    Position(Board),
+    Go(Option<Duration>),
+    Quit,
+}
+
+/// Plays the position it is given, keeping the results of earlier searches between turns.
+#[derive(Default)]
+pub struct Engine {
+    board: Board,
+    table: TranspositionTable,
+}
+
+impl Engine {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn board(&self) -> &Board { &self.board }
+
+    /// Follows the commands until `quit` or the end of the input.
+    pub fn run(&mut self, input: &mut dyn BufRead, output: &mut dyn Write) -> io::Result<()> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            if input.read_line(&mut buf)? == 0 {
+                return Ok(());
+            }
+            if buf.trim().is_empty() {
+                continue;
+            }
+            match parse(&buf) {
+                Ok(EngineCommand::IsReady) => writeln!(output, "readyok")?,
+                Ok(EngineCommand::NewGame) => {
+                    self.board = Board::new();
+                    self.table.clear();
+                }
+                Ok(EngineCommand::Position(board)) => self.board = board,
+                Ok(EngineCommand::Go(movetime)) => self.go(movetime, output)?,
+                Ok(EngineCommand::Quit) => return Ok(()),
+                Err(error) => writeln!(output, "error {}", error)?,
+            }
+            output.flush()?;
+        }
+    }
+
+    /// Searches the current position and writes the statistics and the chosen turn.
+    fn go(&mut self, movetime: Option<Duration>, output: &mut dyn Write) -> io::Result<()> {
+        let start = Instant::now();
+        let movetime = movetime.unwrap_or(DEFAULT_MOVETIME);
+        let deadline = start.checked_add(movetime).unwrap_or(start + MAX_MOVETIME);
+        let result = search(&self.board, &mut self.table, Some(deadline));
+        let elapsed = start.elapsed().as_millis();
+        let best = match result {
+            Some(result) => {
+                let score = match result.outcome {
+                    Outcome::Win(plies) => format!("win {}", plies),
+                    Outcome::Loss(plies) => format!("loss {}", plies),
+                    Outcome::Draw => String::from("draw"),
+                };
+                writeln!(output, "info score {} nodes {} time {}", score, result.nodes, elapsed)?;
+                result.best
+            }
+            None => {
+                writeln!(output, "info score unknown time {}", elapsed)?;
+                let mark = Mark::of(self.board.first_to_move());
+                Some(Heuristic.choose(&self.board, mark))
+            }
+        };
+        match best {
+            Some((i, j)) => writeln!(output, "bestmove {},{}", i, j),
+            None => writeln!(output, "bestmove none"),
+        }
+    }
+}
+
+/// Parses a line of the protocol; a position is checked to be reachable by valid turns.
+pub fn parse(line: &str) -> Result<EngineCommand, String> {
+    let mut words = line.split_whitespace();
+    let command = match words.next() {
+        Some("isready") => EngineCommand::IsReady,
+        Some("newgame") => EngineCommand::NewGame,
+        Some("position") => EngineCommand::Position(position(&mut words)?),
+        Some("go") => match words.next() {
+            None => EngineCommand::Go(None),
+            Some("movetime") => {
+                let value = words.next().ok_or("missing value for movetime")?;
+                let millis = value.parse().map_err(|_| format!("not a number: {}", value))?;
+                EngineCommand::Go(Some(Duration::from_millis(millis)))
+            }
+            Some(word) => return Err(format!("unknown option of go: {}", word)),
+        },
+        Some("quit") => EngineCommand::Quit,
+        Some(word) => return Err(format!("unknown command: {}", word)),
+        None => return Err(String::from("empty command")),
+    };
+    match words.next() {
+        Some(word) => Err(format!("unexpected {}", word)),
+        None => Ok(command),
+    }
+}
+
+/// Reads the board, the win length, and the turns of a `position` command.
+fn position<'a>(words: &mut impl Iterator<Item = &'a str>) -> Result<Board, String> {
+    let notation = words.next().ok_or("missing board")?;
+    let mut words = words.peekable();
+    let win_length = match words.next_if_eq(&"win") {
+        Some(_) => {
+            let value = words.next().ok_or("missing value for win")?;
+            value.parse().map_err(|_| format!("not a number: {}", value))?
+        }
+        None => WIN_LENGTH,
+    };
+    let mut board = match notation {
+        "startpos" if valid_size(SIDE_SIZE, SIDE_SIZE, win_length) => Board::with_size(SIDE_SIZE, SIDE_SIZE, win_length),
+        "startpos" => return Err(format!("cannot make {} in a row on the classic board", win_length)),
+        _ => Board::parse(notation, win_length).map_err(|error| format!("wrong board: {}", error))?,
+    };
+    if board.impossible() {
+        return Err(String::from("wrong board: one player has too many marks"));
+    }
+
+    if words.next_if_eq(&"moves").is_some() {
+        for word in words.by_ref() {
+            let pos = parse_turn(word).ok_or(format!("wrong turn: {}", word))?;
+            if board.status() != Status::Ongoing {
+                return Err(format!("turn {} is made after the game is over", word));
+            }
+            if !board.contains(&pos) || !board.empty(&pos) {
+                return Err(format!("impossible turn: {}", word));
+            }
+            board.set(pos, Mark::of(board.first_to_move()));
+        }
+    }
+    match words.next() {
+        Some(word) => Err(format!("unexpected {}", word)),
+        None => Ok(board),
+    }
+}
+
+/// Parses a turn written as `row,col`.
+pub fn parse_turn(word: &str) -> Option<Position> {
+    let (i, j) = word.split_once(',')?;
+    Some((i.parse().ok()?, j.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Runs a new engine on the commands and returns its answers without the search statistics.
+    fn answers(commands: &str) -> Vec<String> {
+        let mut output = vec![];
+        Engine::new().run(&mut Cursor::new(String::from(commands)), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("info"))
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("isready\n"), Ok(EngineCommand::IsReady));
+        assert_eq!(parse("go\n"), Ok(EngineCommand::Go(None)));
+        assert_eq!(parse("go movetime 50\n"), Ok(EngineCommand::Go(Some(Duration::from_millis(50)))));
+        assert_eq!(parse("position startpos\n"), Ok(EngineCommand::Position(Board::new())));
+        assert_matches!(parse("position x..|.o.|... moves 0,2\n"), Ok(EngineCommand::Position(board))
+            if board == Board::try_from("x.x|.o.|...").unwrap());
+        assert_matches!(parse("position .....|.....|.....|..... win 4 moves 1,1\n"), Ok(EngineCommand::Position(board))
+            if board.win_length() == 4 && board[(1, 1)] == Mark::First);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("go movetime\n").is_err());
+        assert!(parse("go fast\n").is_err());
+        assert!(parse("fly\n").is_err());
+        assert!(parse("quit now\n").is_err());
+        assert!(parse("position\n").is_err());
+        assert!(parse("position x.|..\n").is_err());
+        assert!(parse("position xxx|...|...\n").is_err());
+        assert!(parse("position startpos moves 0,0 0,0\n").is_err());
+        assert!(parse("position startpos moves 3,0\n").is_err());
+        assert!(parse("position xxx|oo.|... moves 2,2\n").is_err());
+        assert!(parse("position startpos win 4\n").is_err());
+    }
+
+    #[test]
+    fn test_plays_best_move() {
+        let answers = answers("isready\nposition xx.|oo.|...\ngo\nposition startpos moves 0,0 1,0 0,1\ngo\nquit\n");
+
+        assert_eq!(answers, vec!["readyok", "bestmove 0,2", "bestmove 0,2"]);
+    }
+
+    #[test]
+    fn test_reports_score() {
+        let mut output = vec![];
+        let input = "position x..|.o.|..x moves 0,2\ngo\n";
+
+        Engine::new().run(&mut Cursor::new(String::from(input)), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("info score win 3 nodes "));
+        assert!(output.ends_with("\nbestmove 2,0\n"));
+    }
+
+    #[test]
+    fn test_game_over() {
+        assert_eq!(answers("position xxx|oo.|...\ngo\n"), vec!["bestmove none"]);
+    }
+
+    #[test]
+    fn test_errors_keep_position() {
+        let answers = answers("position xx.|oo.|...\nposition xx.|oo.|... moves 0,0\nhello\ngo\n");
+
+        assert_eq!(answers.len(), 3);
+        assert!(answers[0].starts_with("error "));
+        assert!(answers[1].starts_with("error "));
+        assert_eq!(answers[2], "bestmove 0,2");
+    }
+
+    #[test]
+    fn test_longest_movetime() {
+        assert_eq!(answers("position xx.|oo.|...\ngo movetime 18446744073709551615\n"), vec!["bestmove 0,2"]);
+    }
+
+    #[test]
+    fn test_falls_back_to_heuristic_when_out_of_time() {
+        let mut output = vec![];
+        let input = "position ....|....|....|.... win 4 moves 1,1\ngo movetime 0\nquit\ngo\n";
+
+        Engine::new().run(&mut Cursor::new(String::from(input)), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("info score unknown"));
+        assert!(lines[1].starts_with("bestmove ") && lines[1] != "bestmove 1,1");
+    }
+}
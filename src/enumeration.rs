@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::board::{Board, BoardIterator, Mark, Status};
+use crate::simulator::Tally;
+
+/// Counts of every game that can be played from a position, ply by ply.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameTree {
+    /// The number of turn sequences reaching each ply, like perft in chess.
+    pub sequences: Vec<usize>,
+    /// The number of distinct positions at each ply.
+    pub positions: Vec<usize>,
+    /// Outcomes of the games that end at each ply.
+    pub outcomes: Vec<Tally>,
+}
+
+impl GameTree {
+    /// Outcomes of all finished games.
+    pub fn total(&self) -> Tally {
+        self.outcomes.iter().fold(Tally::default(), |total, tally| Tally {
+            x_wins: total.x_wins + tally.x_wins,
+            o_wins: total.o_wins + tally.o_wins,
+            ties: total.ties + tally.ties,
+        })
+    }
+
+    /// The number of finished games, counting every turn sequence separately.
+    pub fn games(&self) -> usize { self.total().games() }
+
+    /// The number of distinct positions reachable from the starting one, including itself.
+    pub fn reachable(&self) -> usize { self.positions.iter().sum() }
+}
+
+/// Enumerates every game from the board for at most `depth` turns.
+///
+/// Positions are merged ply by ply, so each distinct position is expanded once and carries the
+/// number of turn sequences that lead to it.
+pub fn enumerate(board: &Board, depth: usize) -> GameTree {
+    let mut tree = GameTree::default();
+    let mut layer = HashMap::from([(board.clone(), 1)]);
+
+    for ply in 0..=depth {
+        let mut tally = Tally::default();
+        let mut next: HashMap<Board, usize> = HashMap::new();
+        tree.sequences.push(layer.values().sum());
+        tree.positions.push(layer.len());
+
+        for (board, count) in layer {
+            match board.status() {
+                Status::Winner(true) => tally.x_wins += count,
+                Status::Winner(false) => tally.o_wins += count,
+                Status::Tie => tally.ties += count,
+                Status::Ongoing if ply < depth => {
+                    let mark = Mark::of(board.first_to_move());
+                    for (pos, _) in BoardIterator::new(&board).filter(|(_, mark)| *mark == Mark::Empty) {
+                        let mut child = board.clone();
+                        child.set(pos, mark);
+                        *next.entry(child).or_insert(0) += count;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        tree.outcomes.push(tally);
+        if next.is_empty() {
+            break;
+        }
+        layer = next;
+    }
+
+    tree
+}
+
+impl fmt::Display for GameTree {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            formatter, "{:<6} {:>12} {:>10} {:>10} {:>10} {:>10}",
+            "Ply", "Sequences", "Positions", "X wins", "O wins", "Ties",
+        )?;
+        for (ply, tally) in self.outcomes.iter().enumerate() {
+            writeln!(
+                formatter, "{:<6} {:>12} {:>10} {:>10} {:>10} {:>10}",
+                ply, self.sequences[ply], self.positions[ply], tally.x_wins, tally.o_wins, tally.ties,
+            )?;
+        }
+        let total = self.total();
+        writeln!(
+            formatter, "{:<6} {:>12} {:>10} {:>10} {:>10} {:>10}",
+            "Total", self.games(), self.reachable(), total.x_wins, total.o_wins, total.ties,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_game_tree() {
+        let tree = enumerate(&Board::new(), 9);
+
+        assert_eq!(tree.sequences, vec![1, 9, 72, 504, 3024, 15120, 54720, 148176, 200448, 127872]);
+        assert_eq!(tree.positions, vec![1, 9, 72, 252, 756, 1260, 1520, 1140, 390, 78]);
+        assert_eq!(tree.games(), 255168);
+        assert_eq!(tree.reachable(), 5478);
+        assert_eq!(tree.total(), Tally {x_wins: 131184, o_wins: 77904, ties: 46080});
+    }
+
+    #[test]
+    fn test_games_end_at_the_earliest_win() {
+        let tree = enumerate(&Board::new(), 9);
+
+        assert_eq!(tree.outcomes[5], Tally {x_wins: 1440, o_wins: 0, ties: 0});
+        assert_eq!(tree.outcomes[6], Tally {x_wins: 0, o_wins: 5328, ties: 0});
+        assert_eq!(tree.outcomes[9].ties, 46080);
+    }
+
+    #[test]
+    fn test_limited_depth() {
+        let tree = enumerate(&Board::new(), 2);
+
+        assert_eq!(tree.sequences, vec![1, 9, 72]);
+        assert_eq!(tree.games(), 0);
+    }
+
+    #[test]
+    fn test_from_finished_position() {
+        let tree = enumerate(&Board::try_from("xxx|oo.|...").unwrap(), 9);
+
+        assert_eq!(tree.sequences, vec![1]);
+        assert_eq!(tree.total(), Tally {x_wins: 1, o_wins: 0, ties: 0});
+    }
+}
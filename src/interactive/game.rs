@@ -1,22 +1,85 @@
-use crate::board::{SIDE_SIZE, Board, BoardIterator, Mark, Position, Status, player_name};
-use crate::interactive::input::{Command, UserInput};
+use std::fs;
+
+use crate::board::{Board, BoardError, BoardIterator, Mark, Position, Status, player_name};
+use crate::interactive::input::{Command, ParseError};
+use crate::interactive::player::Player;
+use crate::record::GameRecord;
 use crate::render::{Message, Render};
 
+/// Record tags that are derived from the game itself rather than kept as they are.
+const GAME_TAGS: [&str; 3] = ["Size", "Win", "Result"];
+
 pub struct Game {
     board: Board,
     curr_player: bool,
     stopped: bool,
+    history: Vec<Position>,
+    undone: Vec<Position>,
+    tags: Vec<(String, String)>,
+}
+
+impl Default for Game {
+    fn default() -> Self { Self::new() }
 }
 
 impl Game {
-    pub fn new() -> Self { Self {board: Board::new(), curr_player: true, stopped: false} }
+    pub fn new() -> Self { Self::with_board(Board::new()).expect("new boards are empty") }
+
+    /// Starts a game on an empty board of any size.
+    ///
+    /// Fails if the board has marks on it, since the game would not know the turns that put them
+    /// there; restore games in progress with `Game::from_record` instead.
+    pub fn with_board(board: Board) -> Result<Self, BoardError> {
+        if BoardIterator::new(&board).any(|(_, mark)| mark != Mark::Empty) {
+            return Err(BoardError::NotEmpty(board.notation()));
+        }
+        Ok(Self {board, curr_player: true, stopped: false, history: vec![], undone: vec![], tags: vec![]})
+    }
+
+    /// Restores a game from its record, checking that every recorded turn is valid.
+    pub fn from_record(record: &GameRecord) -> Result<Self, String> {
+        let mut game = Game::with_board(record.empty_board()?).map_err(|error| error.to_string())?;
+        for (i, pos) in record.moves.iter().enumerate() {
+            if game.board.status() != Status::Ongoing {
+                return Err(format!("turn #{} is made after the game is over", i + 1));
+            }
+            game.valid_turn(pos).map_err(|error| format!("turn #{} [{}, {}]: {}", i + 1, pos.0, pos.1, error))?;
+            game.place(*pos);
+        }
+        for (name, value) in record.tags.iter().filter(|(name, _)| !GAME_TAGS.contains(&name.as_str())) {
+            game.set_tag(name, value);
+        }
+        Ok(game)
+    }
+
+    /// Records the game with its tags and the turns made so far.
+    pub fn record(&self) -> GameRecord {
+        let mut record = GameRecord::new(&self.board, &self.history);
+        for (name, value) in self.tags.iter() {
+            record.set_tag(name, value);
+        }
+        record
+    }
+
+    /// Sets a tag like the names of the players to be saved with the game's record.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = String::from(value),
+            None => self.tags.push((String::from(name), String::from(value))),
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn board(&self) -> &Board { &self.board }
 
     pub fn ongoing(&self) -> bool { !self.stopped && self.board.status() == Status::Ongoing }
 
     pub fn positions(&self, player: bool) -> Vec<Position> {
-        let mut iter = BoardIterator::new(&self.board);
         let mut positions = vec![];
-        while let Some((pos, mark)) = iter.next() {
+        for (pos, mark) in BoardIterator::new(&self.board) {
             if (player && mark == Mark::First) || (!player && mark == Mark::Second) {
                 positions.push(pos);
             }
@@ -24,51 +87,111 @@ impl Game {
         positions
     }
 
-    pub fn play(&mut self, input: &mut dyn UserInput, output: &dyn Render) {
+    /// Plays the game until it is over, taking turns from `x` and `o` in order.
+    pub fn play(&mut self, x: &mut dyn Player, o: &mut dyn Player, output: &dyn Render) {
         output.draw(Message::Welcome);
         output.draw(Message::BoardState(&self.board));
         
         while self.ongoing() {
-            if let Some(cmd) = input.read() {
-               self.turn(&cmd);
+            let cmd = if self.curr_player {
+                x.command(&self.board, output)
             } else {
-                output.draw(Message::UnknownCommand);
+                o.command(&self.board, output)
+            };
+            match cmd {
+                Ok(Command::Undo) => {
+                    if self.undo() {
+                        while self.automated(x, o) && self.undo() {}
+                        output.draw(Message::BoardState(&self.board));
+                    } else {
+                        output.draw(Message::NothingToUndo);
+                    }
+                }
+                Ok(Command::Redo) => {
+                    if self.redo() {
+                        while self.automated(x, o) && self.redo() {}
+                        output.draw(Message::BoardState(&self.board));
+                    } else {
+                        output.draw(Message::NothingToRedo);
+                    }
+                }
+                Ok(Command::Save(path)) => match fs::write(&path, self.record().to_string()) {
+                    Ok(_) => output.draw(Message::GameSaved(&path)),
+                    Err(error) => output.draw(Message::FileError(format!("cannot save {}: {}", path, error))),
+                },
+                Ok(Command::Load(path)) => match load(&path) {
+                    Ok(game) => {
+                        *self = game;
+                        output.draw(Message::GameLoaded(&path));
+                        output.draw(Message::BoardState(&self.board));
+                    }
+                    Err(error) => output.draw(Message::FileError(format!("cannot load {}: {}", path, error))),
+                },
+                Ok(Command::Turn(pos)) => self.turn(pos, output),
+                Ok(Command::Stop) => self.stopped = true,
+                Err(ParseError::EndOfInput) => self.stopped = true,
+                Err(error) => {
+                    output.draw(Message::InputError(&error));
+                    self.stopped = matches!(error, ParseError::ReadFailed(_));
+                }
             }
         }
 
         output.draw(Message::GameOver(&self.board, self.status_string()));
     }
     
-    fn turn(&mut self, cmd: &Command) {
-        let next_player = match cmd {
-            Command::Stop => { 
-                self.stopped = true;
-                false
+    /// The turns made so far, in order.
+    pub fn moves(&self) -> &[Position] { &self.history }
+
+    /// Takes back the last turn. Returns `false` if there are no turns to take back.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(pos) => {
+                self.board.set(pos, Mark::Empty);
+                self.undone.push(pos);
+                self.curr_player = !self.curr_player;
+                true
             }
-            Command::Turn(pos) => {
-                if let Err(error) = self.valid_turn(&pos) {
-                    println!("Impossible turn [{}, {}]: {}; try again!", pos.0, pos.1, error);
-                    false
-                } else {
-                    self.board[*pos] = if self.curr_player {
-                        Mark::First
-                    } else {
-                        Mark::Second
-                    };
-                    true
-                }
+            None => false,
+        }
+    }
+
+    /// Makes the last turn taken back again. Returns `false` if there are no such turns.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(pos) => {
+                self.place(pos);
+                true
             }
-        };
-        if next_player {
-            self.curr_player = !self.curr_player;
-            println!("{}", self.board);
-        };
+            None => false,
+        }
+    }
+
+    /// Checks if the current player is a computer, which is skipped when turns are taken back or replayed.
+    fn automated(&self, x: &dyn Player, o: &dyn Player) -> bool {
+        if self.curr_player { x.automated() } else { o.automated() }
+    }
+
+    fn place(&mut self, pos: Position) {
+        self.board.set(pos, Mark::of(self.curr_player));
+        self.history.push(pos);
+        self.curr_player = !self.curr_player;
+    }
+
+    fn turn(&mut self, pos: Position, output: &dyn Render) {
+        if let Err(error) = self.valid_turn(&pos) {
+            output.draw(Message::InvalidTurn(pos, error));
+        } else {
+            self.place(pos);
+            self.undone.clear();
+            output.draw(Message::MoveMade(&self.board, pos));
+        }
     }
 
     fn valid_turn(&self, pos: &Position) -> Result<(), String> { 
-        if pos.0 >= SIDE_SIZE || pos.1 >= SIDE_SIZE {
-            Err(String::from(format!("each coordinate should be < {}", SIDE_SIZE)))
-        } else if !self.board.empty(&pos) {
+        if !self.board.contains(pos) {
+            Err(format!("coordinates should be < {} and < {}", self.board.rows(), self.board.cols()))
+        } else if !self.board.empty(pos) {
             Err(String::from("cell is not empty"))
         } else {
             Ok(())
@@ -77,7 +200,7 @@ impl Game {
 
     fn status_string(&self) -> String {
         match self.board.status() {
-            Status::Winner(player) => String::from(format!("the player {} wins!", player_name(player))),
+            Status::Winner(player) => format!("the player {} wins!", player_name(player)),
             Status::Tie => String::from("it is a tie!"),
             Status::Ongoing => String::from("stopped early."),
             _ => unreachable!(),
@@ -85,22 +208,62 @@ impl Game {
     }
 }
 
+/// Reads a game's record from the file and restores the game.
+pub fn load(path: &str) -> Result<Game, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    Game::from_record(&GameRecord::parse(&text)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
     use std::io::Cursor;
     use crate::interactive::input::BufferedInput;
+    use crate::interactive::player::Computer;
+    use crate::strategy::{FirstFree, Perfect};
+    use crate::render::{text, ConsoleRender};
+
+    /// Keeps a short line for every message drawn, with boards in their notation.
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl Render for Recorder {
+        fn draw(&self, message: Message) {
+            let line = match message {
+                Message::Welcome => String::from("welcome"),
+                Message::BoardState(board) => format!("board {}", board.notation()),
+                Message::MoveMade(board, pos) => format!("move {},{} {}", pos.0, pos.1, board.notation()),
+                Message::GameOver(board, outcome) => format!("over {} {}", board.notation(), outcome),
+                _ => text(&message),
+            };
+            self.0.borrow_mut().push(line);
+        }
+    }
 
     struct Replay(Game);
 
     impl Replay {
-        fn new() -> Self { Self { 0: Game::new() } }
+        fn new() -> Self { Self(Game::new()) }
         fn run(&mut self, turns: &str) -> String {
-            let mut buf = BufferedInput::new(Cursor::new(String::from(turns)));
-            self.0.play(&mut buf);
+            let (x, o) = split_turns(turns);
+            let mut x = BufferedInput::new(Cursor::new(x));
+            let mut o = BufferedInput::new(Cursor::new(o));
+            self.0.play(&mut x, &mut o, &ConsoleRender);
             self.0.status_string()
         }
     }
+
+    /// Splits the turns, one per line, between the players.
+    fn split_turns(turns: &str) -> (String, String) {
+        let (mut x, mut o) = (String::new(), String::new());
+        for (i, line) in turns.lines().enumerate() {
+            let buf = if i % 2 == 0 { &mut x } else { &mut o };
+            buf.push_str(line);
+            buf.push('\n');
+        }
+        (x, o)
+    }
     
     #[test]
     fn test_new_game() {
@@ -121,6 +284,16 @@ mod tests {
         assert_eq!(replay.0.positions(false), vec![(1, 1)]);
     }
 
+    #[test]
+    fn test_stopped_when_input_ends() {
+        let mut replay = Replay::new();
+
+        replay.run("0,0\n1,\n2,2\n1,1\n");
+
+        assert_eq!(replay.0.status_string(), String::from("stopped early."));
+        assert_eq!(replay.0.moves(), &[(0, 0), (1, 1), (2, 2)]);
+    }
+
     #[test]
     fn test_tie() {
         assert_eq!(
@@ -153,6 +326,188 @@ mod tests {
         assert_eq!(replay.0.positions(false), vec![]);
     }
 
+    #[test]
+    fn test_computer_replies_to_human() {
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(String::from("1,1\ns\n")));
+
+        game.play(&mut x, &mut Computer::new(Perfect::new()), &ConsoleRender);
+
+        assert_eq!(game.positions(true), vec![(1, 1)]);
+        assert_eq!(game.positions(false), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_computers_play_a_tie() {
+        let mut game = Game::new();
+
+        game.play(&mut Computer::new(Perfect::new()), &mut Computer::new(Perfect::new()), &ConsoleRender);
+
+        assert_eq!(game.status_string(), String::from("it is a tie!"));
+    }
+
+    #[test]
+    fn test_game_needs_empty_board() {
+        let error = Game::with_board(Board::try_from("x..|...|...").unwrap()).err();
+        assert_eq!(error, Some(BoardError::NotEmpty(String::from("x..|...|..."))));
+    }
+
+    #[test]
+    fn test_game_on_larger_board() {
+        let mut game = Game::with_board(Board::with_size(4, 5, 4)).unwrap();
+        let mut x = BufferedInput::new(Cursor::new(String::from("3,0\n3,1\n3,2\n3,4\n")));
+        let mut o = BufferedInput::new(Cursor::new(String::from("0,0\n0,1\n0,2\n0,3\n")));
+
+        game.play(&mut x, &mut o, &ConsoleRender);
+
+        assert_eq!(game.status_string(), String::from("the player O wins!"));
+    }
+
+    #[test]
+    fn test_human_against_strategy() {
+        let mut game = Game::new();
+        let mut o = BufferedInput::new(Cursor::new(String::from("1,1\n2,2\n")));
+
+        game.play(&mut Computer::new(FirstFree), &mut o, &ConsoleRender);
+
+        assert_eq!(game.status_string(), String::from("the player X wins!"));
+        assert_eq!(game.positions(true), vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(String::from("0,0\nu\nu\nr\n2,2\n")));
+        let mut o = BufferedInput::new(Cursor::new(String::from("1,1\nr\n0,1\ns\n")));
+
+        game.play(&mut x, &mut o, &ConsoleRender);
+
+        assert_eq!(game.moves(), &[(0, 0), (0, 1), (2, 2)]);
+        assert_eq!(game.positions(true), vec![(0, 0), (2, 2)]);
+        assert_eq!(game.positions(false), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_nothing_to_undo_or_redo() {
+        let mut game = Game::new();
+
+        assert!(!game.undo());
+        assert!(!game.redo());
+        game.turn((1, 1), &ConsoleRender);
+        assert!(game.undo());
+        assert!(!game.undo());
+        assert!(game.redo());
+        assert!(!game.redo());
+        assert_eq!(game.moves(), &[(1, 1)]);
+        assert!(!game.curr_player);
+    }
+
+    #[test]
+    fn test_undo_skips_computer_turns() {
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(String::from("1,1\nu\n2,2\nu\nr\ns\n")));
+
+        game.play(&mut x, &mut Computer::new(FirstFree), &ConsoleRender);
+
+        assert_eq!(game.moves(), &[(2, 2), (0, 0)]);
+        assert!(game.curr_player);
+    }
+
+    #[test]
+    fn test_record_and_restore() {
+        let mut game = Game::new();
+        game.set_tag("X", "human");
+        game.turn((1, 1), &ConsoleRender);
+        game.turn((0, 0), &ConsoleRender);
+        game.turn((2, 2), &ConsoleRender);
+
+        let record = game.record();
+        let restored = Game::from_record(&record).unwrap();
+
+        assert_eq!(record.tag("X"), Some("human"));
+        assert_eq!(record.tag("Result"), Some("*"));
+        assert_eq!(restored.moves(), &[(1, 1), (0, 0), (2, 2)]);
+        assert_eq!(restored.tag("X"), Some("human"));
+        assert_eq!(restored.tag("Result"), None);
+        assert!(!restored.curr_player);
+        assert_eq!(format!("{}", restored.board), format!("{}", game.board));
+    }
+
+    #[test]
+    fn test_restore_invalid_record() {
+        let occupied = GameRecord::parse("1. 1,1 1,1 *").unwrap();
+        let outside = GameRecord::parse("1. 1,1 3,0 *").unwrap();
+        let after_end = GameRecord::parse("1. 0,0 1,0 2. 0,1 1,1 3. 0,2 1,2 *").unwrap();
+
+        assert!(Game::from_record(&occupied).is_err());
+        assert!(Game::from_record(&outside).is_err());
+        assert!(Game::from_record(&after_end).is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_commands() {
+        let path = std::env::temp_dir().join(format!("tictactoe-{}.ttt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(format!("0,0\nsave {}\ns\n", path)));
+        let mut o = BufferedInput::new(Cursor::new(String::from("1,1\n")));
+        game.play(&mut x, &mut o, &ConsoleRender);
+
+        let mut resumed = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(format!("load {}\nu\ns\n", path)));
+        let mut o = BufferedInput::new(Cursor::new(String::from("s\n")));
+        resumed.play(&mut x, &mut o, &ConsoleRender);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(resumed.moves(), &[(0, 0)]);
+        assert!(!resumed.curr_player);
+    }
+
+    #[test]
+    fn test_messages_of_game() {
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(String::from("0,0\n0,1\n0,2\n")));
+        let mut o = BufferedInput::new(Cursor::new(String::from("0,0\n1.1\n1,1\n1,0\n")));
+        let output = Recorder::default();
+
+        game.play(&mut x, &mut o, &output);
+
+        assert_eq!(output.0.into_inner(), vec![
+            "welcome",
+            "board ...|...|...",
+            "move 0,0 x..|...|...",
+            "Impossible turn [0, 0]: cell is not empty; try again!",
+            "Invalid command: unexpected '.' at character 2; try again!",
+            "move 1,1 x..|.o.|...",
+            "move 0,1 xx.|.o.|...",
+            "move 1,0 xx.|oo.|...",
+            "move 0,2 xxx|oo.|...",
+            "over xxx|oo.|... the player X wins!",
+        ]);
+    }
+
+    #[test]
+    fn test_messages_of_undo_and_redo() {
+        let mut game = Game::new();
+        let mut x = BufferedInput::new(Cursor::new(String::from("1,1\nu\nu\nr\n5,5\ns\n")));
+        let output = Recorder::default();
+
+        game.play(&mut x, &mut Computer::new(FirstFree), &output);
+
+        assert_eq!(output.0.into_inner(), vec![
+            "welcome",
+            "board ...|...|...",
+            "move 1,1 ...|.x.|...",
+            "The computer puts O at [0, 0]",
+            "move 0,0 o..|.x.|...",
+            "board ...|...|...",
+            "There are no turns to undo!",
+            "board o..|.x.|...",
+            "Impossible turn [5, 5]: coordinates should be < 3 and < 3; try again!",
+            "over o..|.x.|... stopped early.",
+        ]);
+    }
+
     fn run(turns: &str) -> String {
         let mut replay = Replay::new();
         replay.run(turns)
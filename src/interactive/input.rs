@@ -1,3 +1,4 @@
+use std::fmt;
 use std::io::{stdin, BufRead};
     
 use crate::board::Position;
@@ -6,6 +7,10 @@ use crate::board::Position;
 pub enum Command {
     Turn(Position),
     Stop,
+    Undo,
+    Redo,
+    Save(String),
+    Load(String),
 }
 
 #[derive(Debug)]
@@ -15,8 +20,55 @@ pub enum ParserState {
     CoordY,
 }
 
+/// The reason a line of input is not a command; columns are counted from zero.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    /// The line has nothing but whitespace.
+    Empty,
+    /// The line starts with a word that is not a command.
+    UnknownCommand(String),
+    /// A character that cannot appear at this place of a command.
+    UnexpectedChar { char: char, col: usize },
+    /// The line ends before the comma separating the row from the column.
+    MissingComma { col: usize },
+    /// The line ends right after the comma, without the column.
+    MissingColumn { col: usize },
+    /// The command needs the path of a file but the line ends after its name.
+    MissingPath(String),
+    /// The number ending at the column does not fit in a `usize`.
+    NumberTooLarge { col: usize },
+    /// The command exists but cannot be used here, like saving files in network games.
+    NotAllowed(String),
+    /// There is nothing more to read.
+    EndOfInput,
+    /// The input could not be read.
+    ReadFailed(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(formatter, "empty command"),
+            ParseError::UnknownCommand(word) => write!(formatter, "unknown command '{}'", word),
+            ParseError::UnexpectedChar {char, col} =>
+                write!(formatter, "unexpected '{}' at character {}", char, col + 1),
+            ParseError::MissingComma {col} =>
+                write!(formatter, "expected a comma at character {}", col + 1),
+            ParseError::MissingColumn {col} =>
+                write!(formatter, "expected a column number at character {}", col + 1),
+            ParseError::MissingPath(word) => write!(formatter, "'{}' needs the path of a file", word),
+            ParseError::NumberTooLarge {col} => write!(formatter, "the number at column {} is too large", col),
+            ParseError::NotAllowed(word) => write!(formatter, "'{}' is not available in network games", word),
+            ParseError::EndOfInput => write!(formatter, "the input has ended"),
+            ParseError::ReadFailed(error) => write!(formatter, "failed to read input: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 pub trait UserInput {
-    fn read(&mut self) -> Option<Command>;
+    fn read(&mut self) -> Result<Command, ParseError>;
 }
 
 pub struct BufferedInput<T> {
@@ -26,82 +78,101 @@ pub struct BufferedInput<T> {
 impl<T: BufRead> BufferedInput<T> {
     pub fn new(buffer: T) -> Self { Self { buffer } }
     
-    fn read_from_buffer(&mut self) -> Option<String> {
+    fn read_from_buffer(&mut self) -> Result<String, ParseError> {
         let mut buf = String::new();
-        if let Err(error) = self.buffer.read_line(&mut buf) {
-            println!("failed to read input: {}", error);
-            None
-        } else {
-            Some(buf)
-        }    
+        match self.buffer.read_line(&mut buf) {
+            Ok(0) => Err(ParseError::EndOfInput),
+            Ok(_) => Ok(buf),
+            Err(error) => Err(ParseError::ReadFailed(error.to_string())),
+        }
     }
 }
 
 impl<T: BufRead> UserInput for BufferedInput<T> {
-    fn read(&mut self) -> Option<Command> {
-        self.read_from_buffer().map(|buf| parse(&buf)).flatten()
+    fn read(&mut self) -> Result<Command, ParseError> {
+        self.read_from_buffer().and_then(|buf| parse(&buf))
     }
 }
 
 pub struct Keyboard;
 
 impl UserInput for Keyboard {
-    fn read(&mut self) -> Option<Command> {
+    fn read(&mut self) -> Result<Command, ParseError> {
         BufferedInput::new(stdin().lock()).read()
     }
 }
-    
-fn parse(buf: &String) -> Option<Command> {
+
+/// Parses a line typed by a player into a command.
+pub fn parse(buf: &str) -> Result<Command, ParseError> {
+    let line = buf.trim_end();
+    // Commands are matched as whole words, so that words like `start` or `reset` are not taken for `s` or `r`.
+    let (word, path) = line.trim_start().split_once(' ').unwrap_or((line.trim_start(), ""));
+    match (word, path.trim()) {
+        ("s" | "stop", _) => return Ok(Command::Stop),
+        ("u" | "undo", _) => return Ok(Command::Undo),
+        ("r" | "redo", _) => return Ok(Command::Redo),
+        ("save" | "load", "") => return Err(ParseError::MissingPath(String::from(word))),
+        ("save", path) => return Ok(Command::Save(String::from(path))),
+        ("load", path) => return Ok(Command::Load(String::from(path))),
+        _ if word.starts_with(char::is_alphabetic) => return Err(ParseError::UnknownCommand(String::from(word))),
+        _ => {}
+    }
+
     let mut state = ParserState::Start;
     let mut number: usize = 0;
     let mut x: usize = 0;
 
-    for char in buf.chars() {
+    for (col, char) in line.chars().enumerate() {
         match state {
             ParserState::Start => {
                 match char {
                     '0'..='9' => {
                         state = ParserState::CoordX;
-                        number = 10*number + char.to_digit(10).unwrap() as usize;
-                    },
-                    's' => {
-                        return Some(Command::Stop);
+                        number = push_digit(number, char, col)?;
                     },
                     _ => {
-                        return None;
+                        return Err(ParseError::UnexpectedChar {char, col});
                     }
                 }
             },
             ParserState::CoordX => {
                 match char {
                     '0'..='9' => {
-                        number = 10*number + char.to_digit(10).unwrap() as usize;
+                        number = push_digit(number, char, col)?;
                     },
                     ',' => {
                         x = number;
                         number = 0;
                         state = ParserState::CoordY;
                     },
-                    _ => { return None; }
+                    _ => { return Err(ParseError::UnexpectedChar {char, col}); }
                 }
             },
             ParserState::CoordY => {
                 match char {
                     '0'..='9' => {
-                        number = 10*number + char.to_digit(10).unwrap() as usize;
-                    },
-                    '\n' => {
-                        let y = number;
-                        return Some(Command::Turn((x, y)));
+                        number = push_digit(number, char, col)?;
                     },
-                    _ => { return None; }
+                    _ => { return Err(ParseError::UnexpectedChar {char, col}); }
                 }
             }
         }
     }
-    None
+
+    let col = line.chars().count();
+    match state {
+        ParserState::Start => Err(ParseError::Empty),
+        ParserState::CoordX => Err(ParseError::MissingComma {col}),
+        ParserState::CoordY if line.ends_with(',') => Err(ParseError::MissingColumn {col}),
+        ParserState::CoordY => Ok(Command::Turn((x, number))),
+    }
 }
 
+/// Appends the digit to the number, failing if the number gets too large.
+fn push_digit(number: usize, digit: char, col: usize) -> Result<usize, ParseError> {
+    let digit = digit.to_digit(10).expect("only digits are pushed") as usize;
+    number.checked_mul(10).and_then(|number| number.checked_add(digit)).ok_or(ParseError::NumberTooLarge {col})
+}
 
 #[cfg(test)]
 mod tests {
@@ -110,22 +181,56 @@ mod tests {
 
     #[test]
     fn test_parsing_valid_command() {
-        let valid_turns = strings(vec!["1,2\n", "0,0\n", "stop", "s"]);
+        let valid_turns = strings(vec!["1,2\n", "0,0\n", "2,1", "1,0\r\n", "stop", "s", "undo", "u", "redo", "r"]);
 
-        let parsed_commands: Vec<Command> = valid_turns.iter().map(parse).flatten().collect();
+        let parsed_commands: Vec<Command> = valid_turns.iter().filter_map(|x| parse(x).ok()).collect();
         
         assert_eq!(parsed_commands.len(), valid_turns.len());
     }
 
     #[test]
-    fn test_parsing_invalid_input_into_none() {
+    fn test_parsing_invalid_input_into_errors() {
         let invalid_values = strings(vec!["1.2", "1,1,1", "0", "x"]);
 
-        let parsed_commands: Vec<Command> = invalid_values.iter().map(parse).flatten().collect();
+        let parsed_commands: Vec<Command> = invalid_values.iter().filter_map(|x| parse(x).ok()).collect();
 
         assert_eq!(parsed_commands.len(), 0);
     }
 
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        assert_eq!(parse("1.2\n").unwrap_err(), ParseError::UnexpectedChar {char: '.', col: 1});
+        assert_eq!(parse("1,1,1\n").unwrap_err(), ParseError::UnexpectedChar {char: ',', col: 3});
+        assert_eq!(parse("12\n").unwrap_err(), ParseError::MissingComma {col: 2});
+        assert_eq!(parse("1,\n").unwrap_err(), ParseError::MissingColumn {col: 2});
+        assert_eq!(parse("xyz 1\n").unwrap_err(), ParseError::UnknownCommand(String::from("xyz")));
+        assert_eq!(parse(" \n").unwrap_err(), ParseError::Empty);
+        assert_eq!(parse("1,99999999999999999999999\n").unwrap_err(), ParseError::NumberTooLarge {col: 21});
+        assert_eq!(parse("1;2\n").unwrap_err().to_string(), "unexpected ';' at character 2");
+    }
+
+    #[test]
+    fn test_parsing_undo_and_redo() {
+        assert_matches!(parse("undo\n"), Ok(Command::Undo));
+        assert_matches!(parse("r\n"), Ok(Command::Redo));
+    }
+
+    #[test]
+    fn test_commands_are_whole_words() {
+        for word in ["reset", "random", "reload", "up", "show", "start", "savegame"] {
+            assert_eq!(parse(word).unwrap_err(), ParseError::UnknownCommand(String::from(word)));
+        }
+        assert_matches!(parse("stop\n"), Ok(Command::Stop));
+    }
+
+    #[test]
+    fn test_parsing_save_and_load() {
+        assert_matches!(parse("save game.ttt\n"), Ok(Command::Save(path)) if path == "game.ttt");
+        assert_matches!(parse("load  saved games/1.ttt \n"), Ok(Command::Load(path)) if path == "saved games/1.ttt");
+        assert_eq!(parse("save\n").unwrap_err(), ParseError::MissingPath(String::from("save")));
+        assert_eq!(parse("load  \n").unwrap_err(), ParseError::MissingPath(String::from("load")));
+    }
+
     #[test]
     fn test_reading_from_buffer() {
         let expected = String::from("abc");
@@ -133,7 +238,8 @@ mod tests {
 
         let result = keyboard.read_from_buffer();
 
-        assert_eq!(result, Some(expected));
+        assert_eq!(result, Ok(expected));
+        assert_eq!(keyboard.read_from_buffer(), Err(ParseError::EndOfInput));
     }
 
     #[test]
@@ -142,7 +248,7 @@ mod tests {
 
         let command = keyboard.read();
 
-        assert_matches!(command, Some(Command::Turn((0, 1))));
+        assert_matches!(command, Ok(Command::Turn((0, 1))));
     }
 
     fn strings(vec: Vec<&str>) -> Vec<String> {
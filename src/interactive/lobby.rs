@@ -0,0 +1,397 @@
+//! A server where many players meet to play, watch, and chat, with each game running on its own.
+//!
+//! Clients send one command per line:
+//!
+//! ```text
+//! name NICK             changes the name others see (guest names are given on connection)
+//! list                  lists the games: `game <id> <open|playing> <size> <win> <X> <O> <spectators>`, then `end`
+//! create [RxC] [K]      opens a game on a board of the size (3x3 by default, 12x12 at most) and plays X in it
+//! join ID               plays O in an open game, which starts it
+//! watch ID              follows a game as a spectator
+//! leave                 leaves the game; a player who leaves a started game forfeits it
+//! say TEXT              sends the text to everyone in the same game, or in the lobby outside of games
+//! quit                  disconnects
+//! ```
+//!
+//! Any other line of a player is a command of the game, like `1,1`, sent when the server asks for a
+//! `move` as in `crate::interactive::network`. The server answers with `hello <name>`, `created <id>`,
+//! `joined <id> <X|O>`, `watching <id>`, `left <id>`, `chat <name> <text>`, and `error <reason>`,
+//! and sends the `board`, `move`, `message`, and `gameover` lines of the games. Once a game is over,
+//! everyone in it gets `result <id> <result> <reason>` with the result as written in game records.
+//! A player who disconnects forfeits the game, and so does one who stops reading the lines sent to them:
+//! the server disconnects clients whose lines cannot be written for ten seconds.
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::board::{parse_dimensions, player_name, Board, Status, SIDE_SIZE};
+use crate::interactive::game::Game;
+use crate::interactive::input::{parse, Command, ParseError, UserInput};
+use crate::interactive::network::{protocol_lines, remote_board, remote_command};
+use crate::record::result;
+use crate::render::{Message, Render};
+
+/// Where a client is seated in a game.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Seat {
+    Player(bool),
+    Spectator,
+}
+
+/// The most rows or columns of the boards of lobby games.
+const MAX_SIDE: usize = SIDE_SIZE*4;
+
+/// How long a line may wait to be written before the client is taken for gone.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Client {
+    name: String,
+    /// Lines to send, written by a thread of the client's own.
+    output: Sender<String>,
+    seat: Option<(usize, Seat)>,
+}
+
+/// A game with the clients taking part in it.
+struct Table {
+    board: Board,
+    players: [Option<usize>; 2],
+    spectators: Vec<usize>,
+    /// Lines of the players' commands, once the game has started.
+    inputs: Vec<Sender<String>>,
+    /// The player who has left the game before it was over.
+    forfeit: Option<bool>,
+}
+
+impl Table {
+    fn started(&self) -> bool { !self.inputs.is_empty() }
+
+    fn members(&self) -> Vec<usize> {
+        self.players.iter().flatten().chain(self.spectators.iter()).copied().collect()
+    }
+}
+
+#[derive(Default)]
+struct Lobby {
+    next_client: usize,
+    next_table: usize,
+    clients: HashMap<usize, Client>,
+    tables: BTreeMap<usize, Table>,
+}
+
+impl Lobby {
+    fn send(&self, client: usize, line: &str) {
+        if let Some(client) = self.clients.get(&client) {
+            let _ = client.output.send(String::from(line));
+        }
+    }
+
+    fn send_all(&self, clients: &[usize], line: &str) {
+        for client in clients {
+            self.send(*client, line);
+        }
+    }
+
+    fn name(&self, client: usize) -> String {
+        self.clients.get(&client).map_or(String::from("-"), |client| client.name.clone())
+    }
+}
+
+type Shared = Arc<Mutex<Lobby>>;
+
+fn lock(lobby: &Shared) -> MutexGuard<'_, Lobby> {
+    lobby.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Accepts clients until the listener fails, serving each of them in a thread of its own.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let lobby = Shared::default();
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let lobby = lobby.clone();
+        thread::spawn(move || {
+            let _ = serve_client(&lobby, stream);
+        });
+    }
+    Ok(())
+}
+
+fn serve_client(lobby: &Shared, stream: TcpStream) -> io::Result<()> {
+    // Lines are short and answered at once, so waiting to fill packets only slows the games down.
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let reader = BufReader::new(stream.try_clone()?);
+    let (output, lines) = channel();
+    // Writing in a thread of its own keeps a client that does not read from holding up the lobby.
+    thread::spawn(move || write_lines(stream, lines));
+    let id = {
+        let mut lobby = lock(lobby);
+        lobby.next_client += 1;
+        let id = lobby.next_client;
+        let name = format!("guest{}", id);
+        lobby.clients.insert(id, Client {name: name.clone(), output, seat: None});
+        lobby.send(id, &format!("hello {}", name));
+        id
+    };
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim() == "quit" {
+            break;
+        }
+        handle(lobby, id, line.trim());
+    }
+
+    leave(&mut lock(lobby), id);
+    lock(lobby).clients.remove(&id);
+    Ok(())
+}
+
+/// Writes the lines until the client is removed, disconnecting it once a line cannot be written in time.
+fn write_lines(mut stream: TcpStream, lines: Receiver<String>) {
+    for line in lines {
+        if writeln!(stream, "{}", line).is_err() {
+            break;
+        }
+    }
+    // Ends the reading of the client's commands too, so it leaves the lobby if it has not yet.
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Follows a command of the client.
+fn handle(shared: &Shared, id: usize, line: &str) {
+    let mut lobby = lock(shared);
+    let (word, rest) = line.split_once(' ').map_or((line, ""), |(word, rest)| (word, rest.trim()));
+    let seat = lobby.clients[&id].seat;
+    match word {
+        "name" if rest.is_empty() || rest.contains(char::is_whitespace) => lobby.send(id, "error a name is a single word"),
+        "name" if lobby.clients.values().any(|client| client.name == rest) => {
+            lobby.send(id, &format!("error the name {} is taken", rest));
+        }
+        "name" => {
+            lobby.clients.get_mut(&id).unwrap().name = String::from(rest);
+            lobby.send(id, &format!("hello {}", rest));
+        }
+        "list" => {
+            for (table_id, table) in lobby.tables.iter() {
+                let [x, o] = table.players.map(|player| player.map_or(String::from("-"), |p| lobby.name(p)));
+                lobby.send(id, &format!(
+                    "game {} {} {}x{} {} {} {} {}",
+                    table_id, if table.started() { "playing" } else { "open" },
+                    table.board.rows(), table.board.cols(), table.board.win_length(), x, o, table.spectators.len(),
+                ));
+            }
+            lobby.send(id, "end");
+        }
+        "create" | "join" | "watch" if seat.is_some() => lobby.send(id, "error leave your game first"),
+        "create" => match board(rest) {
+            Ok(board) => {
+                lobby.next_table += 1;
+                let table_id = lobby.next_table;
+                let table = Table {board, players: [Some(id), None], spectators: vec![], inputs: vec![], forfeit: None};
+                lobby.tables.insert(table_id, table);
+                lobby.clients.get_mut(&id).unwrap().seat = Some((table_id, Seat::Player(true)));
+                lobby.send(id, &format!("created {}", table_id));
+            }
+            Err(error) => lobby.send(id, &format!("error {}", error)),
+        },
+        "join" => match rest.parse().ok().filter(|table_id| lobby.tables.contains_key(table_id)) {
+            Some(table_id) if lobby.tables[&table_id].started() => lobby.send(id, "error the game has started"),
+            Some(table_id) => {
+                lobby.tables.get_mut(&table_id).unwrap().players[1] = Some(id);
+                lobby.clients.get_mut(&id).unwrap().seat = Some((table_id, Seat::Player(false)));
+                let players = lobby.tables[&table_id].players.map(Option::unwrap);
+                lobby.send(players[0], &format!("joined {} X", table_id));
+                lobby.send(players[1], &format!("joined {} O", table_id));
+                start(shared, &mut lobby, table_id);
+            }
+            None => lobby.send(id, &format!("error no such game: {}", rest)),
+        },
+        "watch" => match rest.parse().ok().filter(|table_id| lobby.tables.contains_key(table_id)) {
+            Some(table_id) => {
+                lobby.tables.get_mut(&table_id).unwrap().spectators.push(id);
+                lobby.clients.get_mut(&id).unwrap().seat = Some((table_id, Seat::Spectator));
+                lobby.send(id, &format!("watching {}", table_id));
+            }
+            None => lobby.send(id, &format!("error no such game: {}", rest)),
+        },
+        "leave" if seat.is_none() => lobby.send(id, "error you are not in a game"),
+        "leave" => leave(&mut lobby, id),
+        "say" => {
+            let line = format!("chat {} {}", lobby.name(id), rest);
+            let members = match seat {
+                Some((table_id, _)) => lobby.tables[&table_id].members(),
+                None => lobby.clients.iter().filter(|(_, c)| c.seat.is_none()).map(|(id, _)| *id).collect(),
+            };
+            lobby.send_all(&members, &line);
+        }
+        _ => match seat {
+            Some((table_id, Seat::Player(side))) if lobby.tables[&table_id].started() => {
+                let _ = lobby.tables[&table_id].inputs[usize::from(!side)].send(String::from(line));
+            }
+            _ => lobby.send(id, &format!("error unknown command: {}", word)),
+        },
+    }
+}
+
+/// Takes the client out of its game; a player leaving an open game closes it, and a started one is forfeited.
+fn leave(lobby: &mut Lobby, id: usize) {
+    let Some((table_id, seat)) = lobby.clients.get_mut(&id).and_then(|client| client.seat.take()) else {
+        return;
+    };
+    lobby.send(id, &format!("left {}", table_id));
+    let table = lobby.tables.get_mut(&table_id).expect("seated clients have tables");
+    match seat {
+        Seat::Spectator => table.spectators.retain(|spectator| *spectator != id),
+        Seat::Player(side) if table.started() => {
+            table.forfeit = Some(side);
+            table.players[usize::from(!side)] = None;
+            // Stops the game whoever's turn it is; the game's thread announces the result.
+            for input in table.inputs.iter() {
+                let _ = input.send(String::from("s"));
+            }
+        }
+        Seat::Player(_) => {
+            let table = lobby.tables.remove(&table_id).unwrap();
+            for spectator in table.spectators {
+                lobby.clients.get_mut(&spectator).unwrap().seat = None;
+                lobby.send(spectator, &format!("left {}", table_id));
+            }
+        }
+    }
+}
+
+/// Starts the game of the table in a thread of its own.
+fn start(shared: &Shared, lobby: &mut Lobby, table_id: usize) {
+    let mut game = match Game::with_board(lobby.tables[&table_id].board.clone()) {
+        Ok(game) => game,
+        Err(error) => {
+            let members = lobby.tables.remove(&table_id).unwrap().members();
+            lobby.send_all(&members, &format!("error {}", error));
+            for member in members {
+                if let Some(client) = lobby.clients.get_mut(&member) {
+                    client.seat = None;
+                }
+            }
+            return;
+        }
+    };
+    let (x_sender, x_receiver) = channel();
+    let (o_sender, o_receiver) = channel();
+    lobby.tables.get_mut(&table_id).unwrap().inputs = vec![x_sender, o_sender];
+    let shared = shared.clone();
+    thread::spawn(move || {
+        let mut x = TableInput {lobby: shared.clone(), table: table_id, side: true, lines: x_receiver};
+        let mut o = TableInput {lobby: shared.clone(), table: table_id, side: false, lines: o_receiver};
+        game.play(&mut x, &mut o, &TableRender {lobby: shared.clone(), table: table_id});
+        finish(&mut lock(&shared), table_id, &game);
+    });
+}
+
+/// Announces the result of the game and returns everyone at the table to the lobby.
+fn finish(lobby: &mut Lobby, table_id: usize, game: &Game) {
+    let table = lobby.tables.remove(&table_id).expect("only finished games are removed");
+    let line = match (table.forfeit, game.board().status()) {
+        (Some(side), _) => format!("result {} {} {} forfeits", table_id, result(Status::Winner(!side)), player_name(side)),
+        (None, status @ Status::Winner(side)) => format!("result {} {} {} wins", table_id, result(status), player_name(side)),
+        (None, status) => format!("result {} {} {}", table_id, result(status), if status == Status::Tie { "tie" } else { "stopped" }),
+    };
+    let members = table.members();
+    lobby.send_all(&members, &line);
+    for member in members {
+        if let Some(client) = lobby.clients.get_mut(&member) {
+            client.seat = None;
+        }
+    }
+}
+
+/// Commands of a player at a table, who is asked for each one with a `move` line.
+struct TableInput {
+    lobby: Shared,
+    table: usize,
+    side: bool,
+    lines: Receiver<String>,
+}
+
+impl UserInput for TableInput {
+    fn read(&mut self) -> Result<Command, ParseError> {
+        {
+            let lobby = lock(&self.lobby);
+            if let Some(player) = lobby.tables.get(&self.table).and_then(|table| table.players[usize::from(!self.side)]) {
+                lobby.send(player, "move");
+            }
+        }
+        match self.lines.recv() {
+            Ok(line) => remote_command(parse(&line)),
+            Err(_) => Err(ParseError::EndOfInput),
+        }
+    }
+}
+
+/// Sends the messages of a game to everyone at its table.
+struct TableRender {
+    lobby: Shared,
+    table: usize,
+}
+
+impl Render for TableRender {
+    fn draw(&self, message: Message) {
+        let lines = protocol_lines(&message);
+        let lobby = lock(&self.lobby);
+        if let Some(table) = lobby.tables.get(&self.table) {
+            for line in lines.lines() {
+                lobby.send_all(&table.members(), line);
+            }
+        }
+    }
+}
+
+/// Reads the size of a board like `4x4 3`; an empty value means the classic board.
+fn board(value: &str) -> Result<Board, String> {
+    let mut words = value.split_whitespace();
+    let size = words.next().unwrap_or("3x3");
+    // Sizes come from strangers, so they are checked before any cells are allocated.
+    let (rows, cols) = parse_dimensions(size).map_err(|error| error.to_string())?;
+    if rows > MAX_SIDE || cols > MAX_SIDE {
+        return Err(format!("the board can be at most {}x{}", MAX_SIDE, MAX_SIDE));
+    }
+    Board::parse_size(size, words.next()).map_err(|error| error.to_string())
+}
+
+/// Sends the lines of the input to the lobby and shows what it answers until either side disconnects.
+pub fn visit(stream: TcpStream, input: impl BufRead + Send + 'static, output: &dyn Render) -> io::Result<()> {
+    let mut to_server = stream.try_clone()?;
+    thread::spawn(move || {
+        for line in input.lines().map_while(Result::ok) {
+            if writeln!(to_server, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut side = true;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let (kind, rest) = line.split_once(' ').unwrap_or((&line, ""));
+        match kind {
+            "board" => output.draw(Message::BoardState(&remote_board(rest)?)),
+            "move" => output.draw(Message::YourTurn(side)),
+            "message" => output.draw(Message::Remote(rest)),
+            "gameover" => {
+                let (win_length, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (notation, outcome) = rest.split_once(' ').unwrap_or((rest, ""));
+                let board = remote_board(&format!("{} {}", win_length, notation))?;
+                output.draw(Message::GameOver(&board, String::from(outcome)));
+            }
+            "joined" => {
+                side = rest.ends_with('X');
+                output.draw(Message::Remote(&line));
+            }
+            _ => output.draw(Message::Remote(&line)),
+        }
+    }
+    Ok(())
+}
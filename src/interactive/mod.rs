@@ -1,2 +1,7 @@
 pub mod game;
-pub mod input;
\ No newline at end of file
+pub mod input;
+pub mod lobby;
+pub mod network;
+pub mod player;
+pub mod terminal;
+pub mod viewer;
\ No newline at end of file
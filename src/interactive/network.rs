@@ -0,0 +1,267 @@
+//! Games between two players connected over TCP.
+//!
+//! The server sends one message per line:
+//!
+//! ```text
+//! welcome X                      the client plays X (or O)
+//! board 3 x..|.o.|...            the board after a turn, with the number of marks in a row to win
+//! move                           the client is asked for a command
+//! message Invalid command: ...   any other text to show to the player
+//! gameover 3 xxx|oo.|... X wins. the final board and the outcome; the server closes the connection
+//! ```
+//!
+//! A client answers `move` with a line of the same commands a player types at the keyboard, like
+//! `1,1` or `stop`, except for `save` and `load`, since the files are the server's own, and `undo`
+//! and `redo`, which would let a player take back the opponent's turns. A client that disconnects
+//! stops the game.
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::board::Board;
+use crate::interactive::game::Game;
+use crate::interactive::input::{BufferedInput, Command, ParseError, UserInput};
+use crate::render::{text, Message, Render};
+
+/// Commands of a remote player, who is asked for each one with a `move` line.
+pub struct TcpInput {
+    input: BufferedInput<BufReader<TcpStream>>,
+    output: TcpStream,
+}
+
+impl TcpInput {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        let output = stream.try_clone()?;
+        Ok(Self {input: BufferedInput::new(BufReader::new(stream)), output})
+    }
+}
+
+impl UserInput for TcpInput {
+    fn read(&mut self) -> Result<Command, ParseError> {
+        writeln!(self.output, "move").map_err(|error| ParseError::ReadFailed(error.to_string()))?;
+        remote_command(self.input.read())
+    }
+}
+
+/// Refuses the commands remote players cannot give: saving and loading would reach the server's files,
+/// and taking turns back would undo the opponent's turns without asking.
+pub fn remote_command(command: Result<Command, ParseError>) -> Result<Command, ParseError> {
+    match command {
+        Ok(Command::Save(_)) => Err(ParseError::NotAllowed(String::from("save"))),
+        Ok(Command::Load(_)) => Err(ParseError::NotAllowed(String::from("load"))),
+        Ok(Command::Undo) => Err(ParseError::NotAllowed(String::from("undo"))),
+        Ok(Command::Redo) => Err(ParseError::NotAllowed(String::from("redo"))),
+        command => command,
+    }
+}
+
+/// Sends every message to all the connected players; the ones that have disconnected are skipped.
+pub struct TcpRender {
+    streams: Vec<TcpStream>,
+}
+
+impl TcpRender {
+    pub fn new(streams: Vec<TcpStream>) -> Self { Self {streams} }
+}
+
+impl Render for TcpRender {
+    fn draw(&self, message: Message) {
+        let lines = protocol_lines(&message);
+        for mut stream in self.streams.iter() {
+            let _ = stream.write_all(lines.as_bytes());
+        }
+    }
+}
+
+/// The lines of the protocol that carry the message; the welcome is left to the clients.
+pub fn protocol_lines(message: &Message) -> String {
+    match message {
+        Message::Welcome => String::new(),
+        Message::BoardState(board) | Message::MoveMade(board, _) => format!("board {} {}\n", board.win_length(), board.notation()),
+        Message::GameOver(board, outcome) => {
+            format!("gameover {} {} {}\n", board.win_length(), board.notation(), outcome)
+        }
+        _ => text(message).lines().map(|line| format!("message {}\n", line)).collect(),
+    }
+}
+
+/// Waits for two players to connect and plays the game between them; the first one to connect plays X.
+pub fn host(listener: &TcpListener, game: &mut Game) -> io::Result<()> {
+    let mut streams = vec![];
+    for side in ["X", "O"] {
+        let (mut stream, _) = listener.accept()?;
+        writeln!(stream, "welcome {}", side)?;
+        streams.push(stream);
+    }
+    let mut x = TcpInput::new(streams[0].try_clone()?)?;
+    let mut o = TcpInput::new(streams[1].try_clone()?)?;
+    let output = TcpRender::new(streams);
+    game.play(&mut x, &mut o, &output);
+    Ok(())
+}
+
+/// Shows what the server sends and answers its requests with lines from the input until the game is over.
+///
+/// Returns the final board, or `None` if the connection is closed before the game is over.
+pub fn join(server: &mut dyn BufRead, to_server: &mut dyn Write, input: &mut dyn BufRead, output: &dyn Render) -> io::Result<Option<Board>> {
+    let mut player = true;
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if server.read_line(&mut buf)? == 0 {
+            return Ok(None);
+        }
+        let line = buf.trim_end();
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            "welcome" => {
+                player = rest == "X";
+                output.draw(Message::RemoteWelcome);
+                output.draw(Message::Remote(&format!("You play {}", rest)));
+            }
+            "board" => output.draw(Message::BoardState(&remote_board(rest)?)),
+            "move" => {
+                output.draw(Message::YourTurn(player));
+                let mut command = String::new();
+                if input.read_line(&mut command)? == 0 {
+                    return Ok(None);
+                }
+                to_server.write_all(command.trim_end().as_bytes())?;
+                to_server.write_all(b"\n")?;
+                to_server.flush()?;
+            }
+            "message" => output.draw(Message::Remote(rest)),
+            "gameover" => {
+                let (win_length, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (notation, outcome) = rest.split_once(' ').unwrap_or((rest, ""));
+                let board = remote_board(&format!("{} {}", win_length, notation))?;
+                output.draw(Message::GameOver(&board, String::from(outcome)));
+                return Ok(Some(board));
+            }
+            _ => output.draw(Message::Remote(line)),
+        }
+    }
+}
+
+/// Parses a board sent as its win length and notation, like `3 x..|.o.|...`.
+pub fn remote_board(value: &str) -> io::Result<Board> {
+    let invalid = |error: String| io::Error::new(io::ErrorKind::InvalidData, error);
+    let (win_length, notation) = value.split_once(' ').ok_or(invalid(format!("wrong board: {}", value)))?;
+    let win_length = win_length.parse().map_err(|_| invalid(format!("not a number: {}", win_length)))?;
+    Board::parse(notation, win_length).map_err(|error| invalid(format!("wrong board: {}", error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::net::SocketAddr;
+    use std::thread;
+    use crate::board::Status;
+    use crate::render::ConsoleRender;
+
+    /// Hosts a game on a free local port, returning its address and the game once it is over.
+    fn server(board: Board) -> (SocketAddr, thread::JoinHandle<Game>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let mut game = Game::with_board(board).unwrap();
+            host(&listener, &mut game).unwrap();
+            game
+        });
+        (address, handle)
+    }
+
+    /// Connects a client that plays the commands, returning the final board it has seen.
+    fn client(address: SocketAddr, commands: &str) -> thread::JoinHandle<Option<Board>> {
+        let stream = TcpStream::connect(address).unwrap();
+        let commands = String::from(commands);
+        thread::spawn(move || {
+            let mut server = BufReader::new(stream.try_clone().unwrap());
+            let mut to_server = stream;
+            join(&mut server, &mut to_server, &mut Cursor::new(commands), &ConsoleRender).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_game_over_network() {
+        let (address, server) = server(Board::new());
+        let x = client(address, "0,0\n0,1\n0,2\n");
+        let o = client(address, "1,1\n9,9\n1,0\n");
+
+        let game = server.join().unwrap();
+        let x_board = x.join().unwrap().unwrap();
+        let o_board = o.join().unwrap().unwrap();
+
+        assert_eq!(game.moves(), &[(0, 0), (1, 1), (0, 1), (1, 0), (0, 2)]);
+        assert_eq!(x_board.status(), Status::Winner(true));
+        assert_eq!(x_board, o_board);
+    }
+
+    #[test]
+    fn test_disconnect_stops_game() {
+        let (address, server) = server(Board::with_size(4, 4, 3));
+        let x = client(address, "1,1\n");
+        let o = client(address, "2,2\n");
+
+        let game = server.join().unwrap();
+
+        assert_eq!(game.moves(), &[(1, 1), (2, 2)]);
+        assert!(!game.ongoing());
+        assert!(x.join().unwrap().is_none());
+        assert_eq!(o.join().unwrap().unwrap().rows(), 4);
+    }
+
+    #[test]
+    fn test_remote_undo_and_redo_are_refused() {
+        let (address, server) = server(Board::new());
+        let x = client(address, "1,1\n");
+        let o = client(address, "undo\nredo\n0,0\n");
+
+        let game = server.join().unwrap();
+
+        assert_eq!(game.moves(), &[(1, 1), (0, 0)]);
+        assert!(x.join().unwrap().is_none());
+        assert_eq!(o.join().unwrap().unwrap().notation(), "o..|.x.|...");
+    }
+
+    #[test]
+    fn test_remote_save_and_load_are_refused() {
+        let path = std::env::temp_dir().join(format!("tictactoe-remote-{}.ttt", std::process::id()));
+        let (address, server) = server(Board::new());
+        let x = TcpStream::connect(address).unwrap();
+        let _o = TcpStream::connect(address).unwrap();
+        let mut x_lines = BufReader::new(x.try_clone().unwrap()).lines().map(Result::unwrap);
+
+        assert_eq!(x_lines.nth(2).unwrap(), "move");
+        writeln!(&x, "save {}", path.display()).unwrap();
+        assert_eq!(x_lines.next().unwrap(), "message Invalid command: 'save' is not available in network games; try again!");
+        assert_eq!(x_lines.next().unwrap(), "move");
+        writeln!(&x, "load /etc/passwd").unwrap();
+        assert_eq!(x_lines.next().unwrap(), "message Invalid command: 'load' is not available in network games; try again!");
+        writeln!(&x, "s").unwrap();
+
+        assert!(server.join().unwrap().moves().is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_protocol_lines() {
+        let (address, server) = server(Board::new());
+        let x = TcpStream::connect(address).unwrap();
+        let o = TcpStream::connect(address).unwrap();
+        let mut x_lines = BufReader::new(x.try_clone().unwrap()).lines().map(Result::unwrap);
+        let mut o_lines = BufReader::new(o.try_clone().unwrap()).lines().map(Result::unwrap);
+
+        assert_eq!(x_lines.next().unwrap(), "welcome X");
+        assert_eq!(o_lines.next().unwrap(), "welcome O");
+        assert_eq!(x_lines.next().unwrap(), "board 3 ...|...|...");
+        assert_eq!(x_lines.next().unwrap(), "move");
+        writeln!(&x, "1.1").unwrap();
+        assert_eq!(x_lines.next().unwrap(), "message Invalid command: unexpected '.' at character 2; try again!");
+        assert_eq!(x_lines.next().unwrap(), "move");
+        writeln!(&x, "s").unwrap();
+        assert_eq!(o_lines.nth(2).unwrap(), "gameover 3 ...|...|... stopped early.");
+
+        assert!(server.join().unwrap().moves().is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::board::{Board, Mark};
+use crate::interactive::input::{Command, Keyboard, ParseError, UserInput};
+use crate::render::{Message, Render};
+use crate::strategy::{self, Perfect, Strategy};
+
+/// A source of commands for one side of the game.
+pub trait Player {
+    fn command(&mut self, board: &Board, output: &dyn Render) -> Result<Command, ParseError>;
+
+    /// Returns `true` if the player makes turns without a human behind it.
+    fn automated(&self) -> bool { false }
+}
+
+/// Any user input can drive a player; humans see the board through the renderer instead.
+impl<T: UserInput> Player for T {
+    fn command(&mut self, _board: &Board, _output: &dyn Render) -> Result<Command, ParseError> {
+        self.read()
+    }
+}
+
+/// A player that makes turns chosen by a strategy.
+pub struct Computer<S> {
+    strategy: S,
+}
+
+impl<S: Strategy> Computer<S> {
+    pub fn new(strategy: S) -> Self { Self {strategy} }
+}
+
+impl<S: Strategy> Player for Computer<S> {
+    fn command(&mut self, board: &Board, output: &dyn Render) -> Result<Command, ParseError> {
+        let first = board.first_to_move();
+        let pos = self.strategy.choose(board, Mark::of(first));
+        output.draw(Message::ComputerTurn(first, pos));
+        Ok(Command::Turn(pos))
+    }
+
+    fn automated(&self) -> bool { true }
+}
+
+/// Creates a player by its command-line name: `human`, `ai` (same as `perfect`), or a strategy name.
+pub fn from_name(name: &str, seed: u64) -> Option<Box<dyn Player>> {
+    match name {
+        "human" => Some(Box::new(Keyboard)),
+        "ai" => Some(Box::new(Computer::new(Perfect::new()))),
+        _ => strategy::from_name(name, seed).map(|s| Box::new(Computer::new(s)) as Box<dyn Player>),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::ConsoleRender;
+
+    #[test]
+    fn test_computer_makes_winning_turn() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        let command = Computer::new(Perfect::new()).command(&board, &ConsoleRender);
+
+        assert_matches!(command, Ok(Command::Turn((0, 2))));
+    }
+
+    #[test]
+    fn test_player_names() {
+        assert!(from_name("human", 0).is_some());
+        assert!(from_name("ai", 0).is_some());
+        assert!(from_name("heuristic", 0).is_some());
+        assert!(from_name("robot", 0).is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! A full-screen terminal interface where players move a cursor over the board instead of typing
+//! coordinates.
+//!
+//! The arrow keys or `h`, `j`, `k`, `l` move the cursor, Enter or Space puts a mark under it, `u`
+//! and `r` take back and replay turns, and `q`, Esc, or Ctrl-C stop the game. The screen is redrawn
+//! in place after every key and every message of the game, with a status bar at the bottom showing
+//! whose turn it is and the last move.
+use std::cell::RefCell;
+use std::io::{self, stdout, Write};
+use std::rc::Rc;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Print, Stylize};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::board::{char_of, player_name, Board, Mark, Position};
+use crate::interactive::input::{Command, ParseError, UserInput};
+use crate::render::{added_mark, text, Message, Render};
+
+const HELP: &str = "arrows or hjkl: move  Enter: place  u: undo  r: redo  q: stop";
+
+/// What the terminal shows: the board with the cursor, the last message, and the status of the game.
+#[derive(Default)]
+pub struct Screen {
+    board: Board,
+    cursor: Position,
+    /// The player who made the last turn and where, if it is known.
+    last: Option<(bool, Position)>,
+    note: String,
+    outcome: Option<String>,
+}
+
+impl Screen {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn cursor(&self) -> Position { self.cursor }
+
+    pub fn last_move(&self) -> Option<Position> { self.last.map(|(_, pos)| pos) }
+
+    /// Takes the state of the game from the message.
+    pub fn update(&mut self, message: &Message) {
+        match message {
+            Message::Welcome => {}
+            Message::BoardState(board) => {
+                self.last = added_mark(&self.board, board);
+                self.show(board);
+            }
+            Message::MoveMade(board, pos) => {
+                self.last = Some((board[*pos] == Mark::First, *pos));
+                self.show(board);
+            }
+            Message::GameOver(board, outcome) => {
+                self.board = (*board).clone();
+                self.outcome = Some(outcome.clone());
+                self.note = String::from("Press any key to exit");
+            }
+            _ => self.note = text(message).lines().next().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Takes the board, keeping the cursor on it.
+    fn show(&mut self, board: &Board) {
+        self.board = board.clone();
+        self.cursor = (self.cursor.0.min(board.rows() - 1), self.cursor.1.min(board.cols() - 1));
+        self.note.clear();
+    }
+
+    /// Moves the cursor, or returns the command the key stands for.
+    pub fn key(&mut self, key: KeyEvent) -> Option<Command> {
+        let (i, j) = self.cursor;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Some(Command::Stop),
+            KeyCode::Left | KeyCode::Char('h') => self.cursor = (i, j.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.cursor = (i, (j + 1).min(self.board.cols() - 1)),
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = (i.saturating_sub(1), j),
+            KeyCode::Down | KeyCode::Char('j') => self.cursor = ((i + 1).min(self.board.rows() - 1), j),
+            KeyCode::Enter | KeyCode::Char(' ') if !self.board.empty(&self.cursor) => {
+                self.note = String::from("The cell is taken, pick another one!");
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => return Some(Command::Turn(self.cursor)),
+            KeyCode::Char('u') => return Some(Command::Undo),
+            KeyCode::Char('r') => return Some(Command::Redo),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Command::Stop),
+            _ => {}
+        }
+        None
+    }
+
+    /// The text of the status bar.
+    pub fn status(&self) -> String {
+        let state = match &self.outcome {
+            Some(outcome) => format!("Game over: {}", outcome),
+            None => format!("{} to move", player_name(self.board.first_to_move())),
+        };
+        match self.last {
+            Some((player, pos)) => format!("{} | last move: {} at [{}, {}]", state, player_name(player), pos.0, pos.1),
+            None => state,
+        }
+    }
+
+    /// Draws the whole screen from its top-left corner, with the status bar on the last of the rows.
+    pub fn paint(&self, out: &mut impl Write, (width, height): (u16, u16)) -> io::Result<()> {
+        queue!(out, Clear(ClearType::All), MoveTo(2, 0))?;
+        queue!(out, Print(format!(
+            "Tic-Tac-Toe on a {}x{} board, {} in a row to win",
+            self.board.rows(), self.board.cols(), self.board.win_length(),
+        )))?;
+
+        let line = self.board.winning_line().unwrap_or_default();
+        let border = format!("{}+", "+---".repeat(self.board.cols()));
+        let mut y = 2;
+        for i in 0..self.board.rows() {
+            queue!(out, MoveTo(2, y), Print(&border), MoveTo(2, y + 1))?;
+            for j in 0..self.board.cols() {
+                let char = match self.board[(i, j)] {
+                    Mark::Empty => ' ',
+                    mark => char_of(mark),
+                };
+                let char = if line.contains(&(i, j)) { char.to_ascii_uppercase() } else { char };
+                let cell = format!(" {} ", char);
+                queue!(out, Print("|"))?;
+                if (i, j) == self.cursor && self.outcome.is_none() {
+                    queue!(out, Print(cell.reverse()))?;
+                } else if self.last_move() == Some((i, j)) {
+                    queue!(out, Print(cell.bold()))?;
+                } else {
+                    queue!(out, Print(cell))?;
+                }
+            }
+            queue!(out, Print("|"))?;
+            y += 2;
+        }
+        queue!(out, MoveTo(2, y), Print(&border))?;
+        queue!(out, MoveTo(2, y + 2), Print(&self.note), MoveTo(2, y + 3), Print(HELP))?;
+
+        let status = format!(" {:<width$}", self.status(), width = usize::from(width).saturating_sub(1));
+        queue!(out, MoveTo(0, height.saturating_sub(1)), Print(status.reverse()))?;
+        out.flush()
+    }
+}
+
+/// The terminal switched to the full-screen mode while the value lives; it renders the game's messages.
+pub struct Terminal {
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl Terminal {
+    /// Takes over the terminal until the value is dropped.
+    pub fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self {screen: Rc::default()})
+    }
+
+    /// The keyboard of a human player; both players can share the terminal.
+    pub fn input(&self) -> TerminalInput { TerminalInput {screen: self.screen.clone()} }
+
+    /// Waits for any key to be pressed.
+    pub fn wait(&self) -> io::Result<()> {
+        redraw(&self.screen.borrow())?;
+        loop {
+            if let Event::Key(KeyEvent {kind: KeyEventKind::Press, ..}) = event::read()? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Render for Terminal {
+    fn draw(&self, message: Message) {
+        self.screen.borrow_mut().update(&message);
+        let _ = redraw(&self.screen.borrow());
+    }
+}
+
+fn redraw(screen: &Screen) -> io::Result<()> {
+    screen.paint(&mut stdout(), terminal::size()?)
+}
+
+/// Commands chosen with the cursor keys.
+pub struct TerminalInput {
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl UserInput for TerminalInput {
+    fn read(&mut self) -> Result<Command, ParseError> {
+        loop {
+            redraw(&self.screen.borrow()).map_err(|error| ParseError::ReadFailed(error.to_string()))?;
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if let Some(command) = self.screen.borrow_mut().key(key) {
+                        return Ok(command);
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => return Err(ParseError::ReadFailed(error.to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(screen: &mut Screen, codes: &[KeyCode]) -> Option<Command> {
+        codes.iter().fold(None, |_, code| screen.key(KeyEvent::from(*code)))
+    }
+
+    #[test]
+    fn test_cursor_stays_on_board() {
+        let mut screen = Screen::new();
+
+        press(&mut screen, &[KeyCode::Down, KeyCode::Char('j'), KeyCode::Char('j'), KeyCode::Right]);
+        assert_eq!(screen.cursor(), (2, 1));
+        press(&mut screen, &[KeyCode::Char('l'), KeyCode::Char('l'), KeyCode::Up, KeyCode::Char('k'), KeyCode::Char('k')]);
+        assert_eq!(screen.cursor(), (0, 2));
+        press(&mut screen, &[KeyCode::Left, KeyCode::Char('h'), KeyCode::Char('h')]);
+        assert_eq!(screen.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn test_keys_make_commands() {
+        let mut screen = Screen::new();
+
+        assert_matches!(press(&mut screen, &[KeyCode::Down, KeyCode::Right, KeyCode::Enter]), Some(Command::Turn((1, 1))));
+        assert_matches!(press(&mut screen, &[KeyCode::Char('u')]), Some(Command::Undo));
+        assert_matches!(press(&mut screen, &[KeyCode::Char('r')]), Some(Command::Redo));
+        assert_matches!(press(&mut screen, &[KeyCode::Esc]), Some(Command::Stop));
+        assert_matches!(screen.key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Command::Stop));
+        assert_matches!(press(&mut screen, &[KeyCode::Char('x')]), None);
+    }
+
+    #[test]
+    fn test_taken_cell_is_not_a_turn() {
+        let mut screen = Screen::new();
+        screen.update(&Message::BoardState(&Board::try_from("x..|...|...").unwrap()));
+
+        assert_matches!(press(&mut screen, &[KeyCode::Enter]), None);
+        assert!(screen.note.contains("taken"));
+    }
+
+    #[test]
+    fn test_status_shows_turn_and_last_move() {
+        let mut screen = Screen::new();
+        assert_eq!(screen.status(), "X to move");
+
+        screen.update(&Message::BoardState(&Board::try_from("...|.x.|...").unwrap()));
+        assert_eq!(screen.status(), "O to move | last move: X at [1, 1]");
+
+        screen.update(&Message::BoardState(&Board::try_from("...|.x.|..o").unwrap()));
+        assert_eq!(screen.last_move(), Some((2, 2)));
+
+        // Taking a turn back leaves the last move unknown.
+        screen.update(&Message::BoardState(&Board::try_from("...|.x.|...").unwrap()));
+        assert_eq!(screen.status(), "O to move");
+
+        let board = Board::try_from("xxx|oo.|...").unwrap();
+        screen.update(&Message::GameOver(&board, String::from("the player X wins!")));
+        assert_eq!(screen.status(), "Game over: the player X wins!");
+    }
+
+    #[test]
+    fn test_paint_redraws_whole_screen() {
+        let mut screen = Screen::new();
+        screen.update(&Message::BoardState(&Board::try_from("x..|...|...").unwrap()));
+        let mut output = vec![];
+
+        screen.paint(&mut output, (80, 24)).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("\x1b[2J"));
+        assert!(output.contains("+---+---+---+"));
+        assert!(output.contains("O to move | last move: X at [0, 0]"));
+    }
+}
@@ -0,0 +1,173 @@
+use std::io::BufRead;
+
+use crate::board::{Board, Mark};
+use crate::interactive::game::Game;
+use crate::record::GameRecord;
+use crate::render::{Message, Render};
+
+#[derive(Debug, PartialEq)]
+pub enum ViewerCommand {
+    Next,
+    Previous,
+    Jump(usize),
+    First,
+    Last,
+    Help,
+    Quit,
+}
+
+/// Steps through a recorded game one turn at a time.
+pub struct Viewer {
+    record: GameRecord,
+    ply: usize,
+}
+
+impl Viewer {
+    /// Opens the record at its starting position, checking that every recorded turn is valid.
+    pub fn new(record: GameRecord) -> Result<Self, String> {
+        Game::from_record(&record)?;
+        Ok(Self {record, ply: 0})
+    }
+
+    /// The number of turns shown on the board.
+    pub fn ply(&self) -> usize { self.ply }
+
+    pub fn total(&self) -> usize { self.record.moves.len() }
+
+    /// The board after the turns made so far.
+    pub fn board(&self) -> Board {
+        let mut board = self.record.empty_board().expect("checked when the viewer was created");
+        for (i, pos) in self.record.moves[..self.ply].iter().enumerate() {
+            board.set(*pos, Mark::of(i % 2 == 0));
+        }
+        board
+    }
+
+    /// Moves to the position after the given number of turns; returns `false` if there is no such position.
+    pub fn jump(&mut self, ply: usize) -> bool {
+        if ply > self.total() {
+            return false;
+        }
+        self.ply = ply;
+        true
+    }
+
+    /// Reads the commands and draws the positions they lead to until the user quits or the input ends.
+    pub fn run(&mut self, input: &mut dyn BufRead, output: &dyn Render) {
+        output.draw(Message::RecordHeader(&self.record));
+        output.draw(Message::ReplayHelp);
+        self.draw(output);
+
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            match input.read_line(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let moved = match parse(&buf) {
+                Some(ViewerCommand::Next) => self.jump(self.ply + 1),
+                Some(ViewerCommand::Previous) => self.ply > 0 && self.jump(self.ply - 1),
+                Some(ViewerCommand::Jump(ply)) => self.jump(ply),
+                Some(ViewerCommand::First) => self.jump(0),
+                Some(ViewerCommand::Last) => self.jump(self.total()),
+                Some(ViewerCommand::Help) => {
+                    output.draw(Message::ReplayHelp);
+                    continue;
+                }
+                Some(ViewerCommand::Quit) => break,
+                None => {
+                    output.draw(Message::UnknownCommand);
+                    continue;
+                }
+            };
+            if moved {
+                self.draw(output);
+            } else {
+                output.draw(Message::NoSuchTurn(self.total()));
+            }
+        }
+    }
+
+    fn draw(&self, output: &dyn Render) {
+        let last = self.ply.checked_sub(1).map(|i| self.record.moves[i]);
+        output.draw(Message::ReplayPosition(&self.board(), self.ply, self.total(), last));
+    }
+}
+
+/// Parses a viewer command; an empty line moves to the next turn.
+pub fn parse(buf: &str) -> Option<ViewerCommand> {
+    let mut words = buf.split_whitespace();
+    let command = match words.next() {
+        None | Some("n") | Some("next") => ViewerCommand::Next,
+        Some("p") | Some("prev") | Some("previous") => ViewerCommand::Previous,
+        Some("j") | Some("jump") => ViewerCommand::Jump(words.next()?.parse().ok()?),
+        Some("f") | Some("first") => ViewerCommand::First,
+        Some("l") | Some("last") => ViewerCommand::Last,
+        Some("h") | Some("help") => ViewerCommand::Help,
+        Some("q") | Some("quit") => ViewerCommand::Quit,
+        _ => return None,
+    };
+    words.next().is_none().then_some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::render::ConsoleRender;
+
+    fn viewer() -> Viewer {
+        let record = GameRecord::parse("1. 0,0 1,0 2. 0,1 1,1 3. 0,2 1-0").unwrap();
+        Viewer::new(record).unwrap()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("\n"), Some(ViewerCommand::Next));
+        assert_eq!(parse("p\n"), Some(ViewerCommand::Previous));
+        assert_eq!(parse("jump 3\n"), Some(ViewerCommand::Jump(3)));
+        assert_eq!(parse("j\n"), None);
+        assert_eq!(parse("j x\n"), None);
+        assert_eq!(parse("next 2\n"), None);
+        assert_eq!(parse("q\n"), Some(ViewerCommand::Quit));
+    }
+
+    #[test]
+    fn test_board_at_each_turn() {
+        let mut viewer = viewer();
+
+        assert_eq!(format!("{}", viewer.board()), format!("{}", Board::new()));
+        assert!(viewer.jump(4));
+        assert_eq!(format!("{}", viewer.board()), format!("{}", Board::try_from("xx.|oo.|...").unwrap()));
+        assert!(!viewer.jump(6));
+        assert_eq!(viewer.ply(), 4);
+    }
+
+    #[test]
+    fn test_stepping_through_commands() {
+        let mut viewer = viewer();
+        let mut input = Cursor::new(String::from("n\nn\n\np\nj 9\nl\np\nx\n"));
+
+        viewer.run(&mut input, &ConsoleRender);
+
+        assert_eq!(viewer.ply(), 4);
+    }
+
+    #[test]
+    fn test_quit_and_stay_at_bounds() {
+        let mut viewer = viewer();
+        let mut input = Cursor::new(String::from("p\nl\nn\nq\nf\n"));
+
+        viewer.run(&mut input, &ConsoleRender);
+
+        assert_eq!(viewer.ply(), 5);
+    }
+
+    #[test]
+    fn test_invalid_record() {
+        let record = GameRecord::parse("1. 0,0 0,0 *").unwrap();
+
+        assert!(Viewer::new(record).is_err());
+    }
+}
@@ -1,6 +1,14 @@
 #[cfg(test)] #[macro_use]
 extern crate assert_matches;
+pub mod arena;
+pub mod bitboard;
 pub mod board;
+pub mod engine;
+pub mod enumeration;
 pub mod interactive;
 pub mod simulator;
+pub mod solver;
+pub mod strategy;
+pub mod tournament;
+pub mod record;
 pub mod render;
\ No newline at end of file
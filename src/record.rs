@@ -0,0 +1,271 @@
+//! A text format for complete games in the spirit of chess's Portable Game Notation (PGN).
+//!
+//! A record starts with header tags, one per line, followed by an empty line and the numbered
+//! turns that end with the result of the game:
+//!
+//! ```text
+//! [Date "2024.05.01"]
+//! [X "human"]
+//! [O "ai"]
+//! [Size "3x3"]
+//! [Win "3"]
+//! [Result "1/2-1/2"]
+//!
+//! 1. 1,1 0,0 2. 2,2 0,2 3. 0,1 2,1 4. 1,0 1,2 5. 2,0 1/2-1/2
+//! ```
+//!
+//! Each turn is written as `row,col`. The result is `1-0` if X wins, `0-1` if O wins, `1/2-1/2`
+//! for a tie, and `*` for a game that is not over yet. The `Size` and `Win` tags describe the board
+//! and default to the classic 3x3 board with three in a row.
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::board::{Board, Position, Status, SIDE_SIZE};
+
+/// A game's header tags and its turns in the order they were made.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Position>,
+}
+
+impl GameRecord {
+    /// Creates a record of the turns made on a board, with the board's size, the result, and today's date.
+    pub fn new(board: &Board, moves: &[Position]) -> Self {
+        let mut record = Self {tags: vec![], moves: moves.to_vec()};
+        record.set_tag("Date", &today());
+        record.set_tag("Size", &format!("{}x{}", board.rows(), board.cols()));
+        record.set_tag("Win", &board.win_length().to_string());
+        record.set_tag("Result", result(board.status()));
+        record
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a tag, adding the tag to the end of the header if it is new.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = String::from(value),
+            None => self.tags.push((String::from(name), String::from(value))),
+        }
+    }
+
+    /// Creates an empty board of the size given by the tags.
+    pub fn empty_board(&self) -> Result<Board, String> {
+        let size = self.tag("Size").map_or(format!("{}x{}", SIDE_SIZE, SIDE_SIZE), String::from);
+        Board::parse_size(&size, self.tag("Win")).map_err(|error| error.to_string())
+    }
+
+    /// Parses a record from its text form.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut record = GameRecord::default();
+        let mut lines = text.lines().enumerate().peekable();
+
+        while lines.next_if(|(_, line)| line.trim().is_empty()).is_some() {}
+        while let Some((line_no, line)) = lines.next_if(|(_, line)| line.trim().starts_with('[')) {
+            let (name, value) = parse_tag(line.trim()).ok_or(format!("line {}: wrong tag: {}", line_no + 1, line))?;
+            record.tags.push((name, value));
+        }
+
+        for (line_no, line) in lines {
+            for token in line.split_whitespace() {
+                if is_turn_number(token) || ["1-0", "0-1", "1/2-1/2", "*"].contains(&token) {
+                    continue;
+                }
+                let pos = token.split_once(',').and_then(|(i, j)| Some((i.parse().ok()?, j.parse().ok()?)));
+                record.moves.push(pos.ok_or(format!("line {}: wrong turn: {}", line_no + 1, token))?);
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// Parses all records from a text where they follow one another, like a PGN file with many games.
+pub fn parse_all(text: &str) -> Result<Vec<GameRecord>, String> {
+    let mut chunks: Vec<String> = vec![];
+    let mut in_moves = false;
+    for line in text.lines() {
+        let is_tag = line.trim().starts_with('[');
+        if chunks.is_empty() || (is_tag && in_moves) {
+            chunks.push(String::new());
+        }
+        if !line.trim().is_empty() {
+            in_moves = !is_tag;
+        }
+        let chunk = chunks.last_mut().unwrap();
+        chunk.push_str(line);
+        chunk.push('\n');
+    }
+    chunks.iter()
+        .filter(|chunk| !chunk.trim().is_empty())
+        .enumerate()
+        .map(|(i, chunk)| GameRecord::parse(chunk).map_err(|error| format!("game #{}: {}", i + 1, error)))
+        .collect()
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.tags.iter() {
+            writeln!(formatter, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        writeln!(formatter)?;
+        for (i, pos) in self.moves.iter().enumerate() {
+            if i % 2 == 0 {
+                write!(formatter, "{}. ", i / 2 + 1)?;
+            }
+            write!(formatter, "{},{} ", pos.0, pos.1)?;
+        }
+        writeln!(formatter, "{}", self.tag("Result").unwrap_or("*"))
+    }
+}
+
+/// The result of a game as written in records.
+pub fn result(status: Status) -> &'static str {
+    match status {
+        Status::Winner(true) => "1-0",
+        Status::Winner(false) => "0-1",
+        Status::Tie => "1/2-1/2",
+        _ => "*",
+    }
+}
+
+/// Parses a tag like `[Name "value"]` into its name and unescaped value.
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, quoted) = inner.split_once(' ')?;
+    let quoted = quoted.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(char) = chars.next() {
+        value.push(if char == '\\' { chars.next()? } else { char });
+    }
+    Some((String::from(name), value))
+}
+
+fn is_turn_number(token: &str) -> bool {
+    token.strip_suffix('.').is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Today's date in the `YYYY.MM.DD` form.
+fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86400) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+/// Converts days since 1970-01-01 into a Gregorian calendar date.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORD: &str = "[Date \"2024.05.01\"]\n\
+                          [X \"human\"]\n\
+                          [O \"ai\"]\n\
+                          [Size \"3x3\"]\n\
+                          [Win \"3\"]\n\
+                          [Result \"1-0\"]\n\
+                          \n\
+                          1. 0,0 1,0 2. 0,1 1,1 3. 0,2 1-0\n";
+
+    #[test]
+    fn test_parse_record() {
+        let record = GameRecord::parse(RECORD).unwrap();
+
+        assert_eq!(record.tag("X"), Some("human"));
+        assert_eq!(record.tag("Result"), Some("1-0"));
+        assert_eq!(record.tag("Event"), None);
+        assert_eq!(record.moves, vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn test_parse_record_after_blank_lines() {
+        let record = GameRecord::parse(&format!("\n  \n{}", RECORD)).unwrap();
+
+        assert_eq!(record, GameRecord::parse(RECORD).unwrap());
+        assert_eq!(parse_all(&format!("\n{}", RECORD)).unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn test_format_record() {
+        let record = GameRecord::parse(RECORD).unwrap();
+
+        assert_eq!(format!("{}", record), RECORD);
+    }
+
+    #[test]
+    fn test_record_of_board() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        let record = GameRecord::new(&board, &[(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        assert_eq!(record.tag("Size"), Some("3x3"));
+        assert_eq!(record.tag("Result"), Some("*"));
+        assert_eq!(GameRecord::parse(&format!("{}", record)).unwrap(), record);
+    }
+
+    #[test]
+    fn test_escaped_tags() {
+        let mut record = GameRecord::default();
+        record.set_tag("Event", "the \"final\" \\ round");
+
+        let parsed = GameRecord::parse(&format!("{}", record)).unwrap();
+
+        assert_eq!(parsed.tag("Event"), Some("the \"final\" \\ round"));
+    }
+
+    #[test]
+    fn test_empty_board_of_record() {
+        let mut record = GameRecord::default();
+
+        assert_eq!(record.empty_board().unwrap().size(), 9);
+        record.set_tag("Size", "4x5");
+        record.set_tag("Win", "4");
+        let board = record.empty_board().unwrap();
+        assert_eq!((board.rows(), board.cols(), board.win_length()), (4, 5, 4));
+        record.set_tag("Win", "5");
+        assert!(record.empty_board().is_err());
+    }
+
+    #[test]
+    fn test_parse_many_records() {
+        let text = format!("{}\n{}\n[Round \"3\"]\n\n1. 1,1 *\n", RECORD, RECORD);
+
+        let records = parse_all(&text).unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], GameRecord::parse(RECORD).unwrap());
+        assert_eq!(records[2].tag("Round"), Some("3"));
+        assert_eq!(records[2].moves, vec![(1, 1)]);
+        assert!(parse_all("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        assert!(GameRecord::parse("[Date 2024]\n\n1. 0,0 *").is_err());
+        assert!(GameRecord::parse("1. 0,0 x,1 *").is_err());
+        assert!(GameRecord::parse("1. 0;0 *").is_err());
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19844), (2024, 5, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+    }
+}
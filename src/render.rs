@@ -1,10 +1,39 @@
-use crate::board::Board;
+use std::cell::RefCell;
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+use crossterm::style::{StyledContent, Stylize};
+
+use crate::board::{char_of, player_name, Board, Mark, Position};
+use crate::interactive::input::ParseError;
+use crate::record::GameRecord;
 
 pub enum Message<'a> {
     Welcome,
     BoardState(&'a Board),
+    /// The board after a turn made at the position.
+    MoveMade(&'a Board, Position),
+    /// A turn that cannot be made at the position, with the reason.
+    InvalidTurn(Position, String),
     UnknownCommand,
+    InputError(&'a ParseError),
+    NothingToUndo,
+    NothingToRedo,
+    GameSaved(&'a str),
+    GameLoaded(&'a str),
+    FileError(String),
+    ComputerTurn(bool, Position),
+    RecordHeader(&'a GameRecord),
+    ReplayHelp,
+    ReplayPosition(&'a Board, usize, usize, Option<Position>),
+    NoSuchTurn(usize),
     GameOver(&'a Board, String),
+    /// The help of a networked game, which has fewer commands than a local one.
+    RemoteWelcome,
+    /// A line of text sent by the server of a networked game.
+    Remote(&'a str),
+    /// The player of a networked game is asked for a command.
+    YourTurn(bool),
 }
 
 pub trait Render {
@@ -15,19 +44,262 @@ pub struct ConsoleRender;
 
 impl Render for ConsoleRender {
     fn draw(&self, message: Message) {
-        println!("{}", match message {
-            Message::Welcome => String::from(
-                "Welcome to the Tic-Tac-Toe game!\n\
-                 Commands:\n\
-                 (1) put mark at x row and y column: x,y\n\
-                 (2) stop the game: [s]top (or Ctrl-C)\n"
+        println!("{}", text(&message));
+    }
+}
+
+/// Draws boards with Unicode lines, colored marks, and coordinates, highlighting the last move.
+#[derive(Default)]
+pub struct FancyRender {
+    /// The last board drawn and the move that led to it, if it is known.
+    last: RefCell<Option<(Board, Option<Position>)>>,
+}
+
+impl FancyRender {
+    pub fn new() -> Self { Self::default() }
+
+    /// The text of a message; boards are drawn the fancy way, everything else as the console does.
+    pub fn text(&self, message: &Message) -> String {
+        let mut last = self.last.borrow_mut();
+        let last_move = |board: &Board| match last.as_ref() {
+            Some((previous, _)) if previous == board => last.as_ref().and_then(|(_, pos)| *pos),
+            Some((previous, _)) => added_mark(previous, board).map(|(_, pos)| pos),
+            None => None,
+        };
+        match message {
+            Message::BoardState(board) => {
+                let pos = last_move(board);
+                *last = Some(((*board).clone(), pos));
+                fancy_board(board, pos)
+            }
+            Message::MoveMade(board, pos) => {
+                *last = Some(((*board).clone(), Some(*pos)));
+                fancy_board(board, Some(*pos))
+            }
+            Message::ReplayPosition(board, ply, total, Some(pos)) => format!(
+                "Turn {} of {}: {} at [{}, {}]\n{}",
+                ply, total, player_name(ply % 2 == 1), pos.0, pos.1, fancy_board(board, Some(*pos)),
             ),
-            Message::BoardState(board) => format!("{}", board),
-            Message::UnknownCommand => String::from("Unknown command, try again!"),
+            Message::ReplayPosition(board, _, total, None) => {
+                format!("Start of the game, {} turns in total\n{}", total, fancy_board(board, None))
+            }
             Message::GameOver(board, outcome) => format!(
                 "The game is over: {}\nThe final board's state:\n{}",
-                outcome, board,
+                outcome, fancy_board(board, last_move(board)),
+            ),
+            _ => text(message),
+        }
+    }
+}
+
+impl Render for FancyRender {
+    fn draw(&self, message: Message) {
+        println!("{}", self.text(&message));
+    }
+}
+
+/// The renderer for the console: fancy if the output is a terminal and colors are not turned off with
+/// `NO_COLOR`, plain otherwise.
+pub fn console() -> Box<dyn Render> {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if no_color || !stdout().is_terminal() {
+        Box::new(ConsoleRender)
+    } else {
+        Box::new(FancyRender::new())
+    }
+}
+
+/// The mark that is on the new board but not on the old one, with its player, if it is the only difference.
+pub fn added_mark(old: &Board, new: &Board) -> Option<(bool, Position)> {
+    if (old.rows(), old.cols()) != (new.rows(), new.cols()) {
+        return None;
+    }
+    let mut changed = (0..old.rows())
+        .flat_map(|i| (0..old.cols()).map(move |j| (i, j)))
+        .filter(|pos| old[*pos] != new[*pos]);
+    match (changed.next(), changed.next()) {
+        (Some(pos), None) if old.empty(&pos) => Some((new[pos] == Mark::First, pos)),
+        _ => None,
+    }
+}
+
+/// Draws the board in a grid of Unicode lines with the numbers of rows and columns around it.
+///
+/// X is red and O is blue, the winning line is in capitals, and the last move is shown inverted.
+pub fn fancy_board(board: &Board, last: Option<Position>) -> String {
+    let line = board.winning_line().unwrap_or_default();
+    let label = (board.rows().max(1) - 1).to_string().len();
+    let border = |left: &str, middle: &str, right: &str| {
+        format!("{:label$} {}{}{}\n", "", left, vec!["───"; board.cols()].join(middle), right, label = label)
+    };
+
+    let mut formatted = format!("{:label$} ", "", label = label);
+    for j in 0..board.cols() {
+        formatted.push_str(&format!(" {:^3}", j));
+    }
+    formatted.push('\n');
+    formatted.push_str(&border("┌", "┬", "┐"));
+    for i in 0..board.rows() {
+        if i > 0 {
+            formatted.push_str(&border("├", "┼", "┤"));
+        }
+        formatted.push_str(&format!("{:>label$} │", i, label = label));
+        for j in 0..board.cols() {
+            let winning = line.contains(&(i, j));
+            let cell = match board[(i, j)] {
+                Mark::Empty => StyledContent::new(Default::default(), String::from("   ")),
+                Mark::First => format!(" {} ", if winning { 'X' } else { 'x' }).red().bold(),
+                Mark::Second => format!(" {} ", if winning { 'O' } else { 'o' }).blue().bold(),
+            };
+            let cell = if last == Some((i, j)) { cell.reverse() } else { cell };
+            formatted.push_str(&format!("{}│", cell));
+        }
+        formatted.push('\n');
+    }
+    formatted.push_str(&border("└", "┴", "┘"));
+    formatted
+}
+
+/// The text of a message as the console shows it.
+pub fn text(message: &Message) -> String {
+    match message {
+        Message::Welcome => String::from(
+            "Welcome to the Tic-Tac-Toe game!\n\
+             Commands:\n\
+             (1) put mark at x row and y column: x,y\n\
+             (2) take back or replay a turn: [u]ndo, [r]edo\n\
+             (3) save the game to a file or continue a saved one: save <path>, load <path>\n\
+             (4) stop the game: [s]top (or Ctrl-C)\n"
+        ),
+        Message::RemoteWelcome => String::from(
+            "Welcome to the Tic-Tac-Toe game over the network!\n\
+             Commands:\n\
+             (1) put mark at x row and y column: x,y\n\
+             (2) stop the game: [s]top\n"
+        ),
+        Message::BoardState(board) | Message::MoveMade(board, _) => format!("{}", board),
+        Message::InvalidTurn(pos, error) => format!("Impossible turn [{}, {}]: {}; try again!", pos.0, pos.1, error),
+        Message::UnknownCommand => String::from("Unknown command, try again!"),
+        Message::InputError(error @ ParseError::ReadFailed(_)) => format!("{}", error),
+        Message::InputError(error) => format!("Invalid command: {}; try again!", error),
+        Message::NothingToUndo => String::from("There are no turns to undo!"),
+        Message::NothingToRedo => String::from("There are no turns to redo!"),
+        Message::GameSaved(path) => format!("The game is saved to {}", path),
+        Message::GameLoaded(path) => format!("The game is loaded from {}", path),
+        Message::FileError(error) => format!("{}; try again!", error),
+        Message::ComputerTurn(player, pos) => format!(
+            "The computer puts {} at [{}, {}]", player_name(*player), pos.0, pos.1,
+        ),
+        Message::RecordHeader(record) => record.tags.iter()
+            .map(|(name, value)| format!("{}: {}\n", name, value))
+            .collect(),
+        Message::ReplayHelp => String::from(
+            "Commands:\n\
+             (1) go to the next or previous turn: [n]ext (or Enter), [p]rev\n\
+             (2) go to the position after N turns: [j]ump N\n\
+             (3) go to the start or the end of the game: [f]irst, [l]ast\n\
+             (4) show this help or quit: [h]elp, [q]uit\n"
+        ),
+        Message::ReplayPosition(board, ply, total, last) => match last {
+            Some(pos) => format!(
+                "Turn {} of {}: {} at [{}, {}]\n{}",
+                ply, total, player_name(ply % 2 == 1), pos.0, pos.1, highlighted(board),
             ),
-        });
+            None => format!("Start of the game, {} turns in total\n{}", total, board),
+        },
+        Message::NoSuchTurn(total) => format!("There is no such turn, the game has {} turns!", total),
+        Message::GameOver(board, outcome) => format!(
+            "The game is over: {}\nThe final board's state:\n{}",
+            outcome, highlighted(board),
+        ),
+        Message::Remote(text) => String::from(*text),
+        Message::YourTurn(player) => format!("Your turn, you play {}:", player_name(*player)),
+    }
+}
+
+/// Formats the board like its `Display` does but with the winning line in capitals.
+fn highlighted(board: &Board) -> String {
+    let line = board.winning_line().unwrap_or_default();
+    let border = format!("+{}+\n", "-".repeat(board.cols()));
+    let mut formatted = border.clone();
+    for i in 0..board.rows() {
+        formatted.push('|');
+        for j in 0..board.cols() {
+            let char = char_of(board[(i, j)]);
+            formatted.push(if line.contains(&(i, j)) { char.to_ascii_uppercase() } else { char });
+        }
+        formatted.push_str("|\n");
+    }
+    formatted.push_str(&border);
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_winning_line_highlighted() {
+        let board = Board::try_from("xoo|.x.|..x").unwrap();
+
+        assert_eq!(highlighted(&board),
+                   "+---+\n\
+                    |Xoo|\n\
+                    |.X.|\n\
+                    |..X|\n\
+                    +---+\n");
+    }
+
+    #[test]
+    fn test_fancy_board() {
+        let board = Board::try_from("...|.x.|...").unwrap();
+
+        let formatted = fancy_board(&board, None);
+
+        let lines: Vec<&str> = formatted.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "    0   1   2 ");
+        assert_eq!(lines[1], "  ┌───┬───┬───┐");
+        assert_eq!(lines[2], "0 │   │   │   │");
+        assert_eq!(lines[3], "  ├───┼───┼───┤");
+        assert_eq!(lines[4], format!("1 │   │{}│   │", " x ".red().bold()));
+        assert_eq!(lines[7], "  └───┴───┴───┘");
+    }
+
+    #[test]
+    fn test_fancy_board_labels_wide_boards() {
+        let formatted = fancy_board(&Board::with_size(11, 11, 5), None);
+
+        assert!(formatted.starts_with("     0   1 "));
+        assert!(formatted.contains("\n10 │   │"));
+        assert!(formatted.lines().skip(1).all(|line| line.chars().count() == 48));
+    }
+
+    #[test]
+    fn test_fancy_highlights_last_move_and_winning_line() {
+        let render = FancyRender::new();
+        render.text(&Message::BoardState(&Board::try_from("xx.|oo.|...").unwrap()));
+
+        let text = render.text(&Message::BoardState(&Board::try_from("xxx|oo.|...").unwrap()));
+
+        assert!(text.contains(&format!("│{}│{}│{}│", " X ".red().bold(), " X ".red().bold(), " X ".red().bold().reverse())));
+        assert!(render.text(&Message::UnknownCommand).starts_with("Unknown command"));
+    }
+
+    #[test]
+    fn test_nothing_highlighted_after_undo() {
+        let render = FancyRender::new();
+        render.text(&Message::BoardState(&Board::try_from("x..|.o.|...").unwrap()));
+
+        let text = render.text(&Message::BoardState(&Board::try_from("x..|...|...").unwrap()));
+
+        assert!(!text.contains("\x1b[7m"));
+    }
+
+    #[test]
+    fn test_nothing_highlighted_without_winner() {
+        let board = Board::try_from("xoo|.x.|...").unwrap();
+
+        assert_eq!(highlighted(&board), format!("{}", board));
     }
 }
\ No newline at end of file
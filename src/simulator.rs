@@ -1,48 +1,232 @@
-use std::collections::HashSet;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::thread;
 
 use rand::prelude::*;
-use crate::board::{player_name, Board, Position, Mark, Status, BOARD_SIZE, SIDE_SIZE};
+use crate::board::{player_name, Board, Position, Mark, Status};
+use crate::strategy::{Random, Strategy};
+
+/// A simulated game: the final state of the board and the turns that led to it.
+pub struct Simulation {
+    pub board: Board,
+    pub moves: Vec<Position>,
+}
+
+/// The header of the CSV written with `Simulation::csv`.
+pub const CSV_HEADER: &str = "game,moves,board,status,plies";
+
+impl Simulation {
+    /// The game numbered `game` as a JSON object on one line, like
+    /// `{"game":1,"moves":[[0,0],[1,1]],"board":"x..|.o.|...","status":"ongoing","plies":2}`.
+    pub fn json(&self, game: usize) -> String {
+        let moves: Vec<String> = self.moves.iter().map(|(i, j)| format!("[{},{}]", i, j)).collect();
+        format!(
+            "{{\"game\":{},\"moves\":[{}],\"board\":\"{}\",\"status\":\"{}\",\"plies\":{}}}",
+            game, moves.join(","), self.board.notation(), status_name(self.board.status()), self.moves.len(),
+        )
+    }
+
+    /// The game numbered `game` as a line of CSV with the columns of `CSV_HEADER`; the turns are
+    /// separated by spaces, like `1,"0,0 1,1",x..|.o.|...,ongoing,2`.
+    pub fn csv(&self, game: usize) -> String {
+        let moves: Vec<String> = self.moves.iter().map(|(i, j)| format!("{},{}", i, j)).collect();
+        format!(
+            "{},\"{}\",{},{},{}",
+            game, moves.join(" "), self.board.notation(), status_name(self.board.status()), self.moves.len(),
+        )
+    }
+}
+
+/// The status of a game as written in structured output.
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Ongoing => "ongoing",
+        Status::Winner(true) => "x_wins",
+        Status::Winner(false) => "o_wins",
+        Status::Tie => "tie",
+        Status::Impossible => "impossible",
+    }
+}
+
+/// The number of games won by each player or tied.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Tally {
+    pub x_wins: usize,
+    pub o_wins: usize,
+    pub ties: usize,
+}
+
+impl Tally {
+    pub fn games(&self) -> usize { self.x_wins + self.o_wins + self.ties }
+
+    fn add(&mut self, status: Status) {
+        match status {
+            Status::Winner(true) => self.x_wins += 1,
+            Status::Winner(false) => self.o_wins += 1,
+            Status::Tie => self.ties += 1,
+            _ => {}
+        }
+    }
+
+    fn merge(&mut self, other: &Tally) {
+        self.x_wins += other.x_wins;
+        self.o_wins += other.o_wins;
+        self.ties += other.ties;
+    }
+}
+
+/// Aggregated statistics of many simulated games.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    /// Outcomes of all games.
+    pub total: Tally,
+    /// Number of games per number of turns made.
+    pub lengths: BTreeMap<usize, usize>,
+    /// Outcomes of the games per cell of the opening turn.
+    pub openings: BTreeMap<Position, Tally>,
+}
+
+impl SimulationReport {
+    pub fn new() -> Self { Self::default() }
+
+    /// Accounts for one more finished game.
+    pub fn add(&mut self, game: &Simulation) {
+        let status = game.board.status();
+        self.total.add(status);
+        *self.lengths.entry(game.moves.len()).or_insert(0) += 1;
+        if let Some(opening) = game.moves.first() {
+            self.openings.entry(*opening).or_default().add(status);
+        }
+    }
+
+    /// Combines the statistics of two reports.
+    pub fn merge(&mut self, other: &SimulationReport) {
+        self.total.merge(&other.total);
+        for (length, games) in other.lengths.iter() {
+            *self.lengths.entry(*length).or_insert(0) += games;
+        }
+        for (opening, tally) in other.openings.iter() {
+            self.openings.entry(*opening).or_default().merge(tally);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Simulation> for SimulationReport {
+    fn from_iter<I: IntoIterator<Item = &'a Simulation>>(iter: I) -> Self {
+        let mut report = SimulationReport::new();
+        for game in iter {
+            report.add(game);
+        }
+        report
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let games = self.total.games();
+        let percent = |count: usize| if games == 0 { 0.0 } else { 100.0 * count as f64 / games as f64 };
+
+        writeln!(formatter, "Games: {}", games)?;
+        writeln!(
+            formatter, "X wins: {} ({:.1}%), O wins: {} ({:.1}%), ties: {} ({:.1}%)",
+            self.total.x_wins, percent(self.total.x_wins),
+            self.total.o_wins, percent(self.total.o_wins),
+            self.total.ties, percent(self.total.ties),
+        )?;
+
+        writeln!(formatter, "\n{:<8} {:>9} {:>9}", "Turns", "Games", "Share")?;
+        for (length, count) in self.lengths.iter() {
+            writeln!(formatter, "{:<8} {:>9} {:>8.1}%", length, count, percent(*count))?;
+        }
+
+        writeln!(formatter, "\n{:<8} {:>9} {:>9} {:>9} {:>9}", "Opening", "Games", "X wins", "O wins", "Ties")?;
+        for ((i, j), tally) in self.openings.iter() {
+            writeln!(
+                formatter, "{:<8} {:>9} {:>9} {:>9} {:>9}",
+                format!("[{}, {}]", i, j), tally.games(), tally.x_wins, tally.o_wins, tally.ties,
+            )?;
+        }
+        Ok(())
+    }
+}
 
 /// Simulates N games.
 ///
 /// Each simulation runs a random sequence of turns for a newly created game board.
-/// The results returns as a vector of final states.
-pub fn simulate(n: usize, logged: bool) -> Vec<Board> {
-    let mut rng = thread_rng();
-    (0..n).map(move |_| simulate_one(&mut rng, logged)).collect()
-}
-
-/// Simulates a single game and returns the final state of a board.
-pub fn simulate_one(rng: &mut ThreadRng, logged: bool) -> Board {
-    let mut board = Board::new();
-    let mut turns: HashSet<Position> = HashSet::new();
-    let mut first = true;
-
-    for turn in 1..=BOARD_SIZE {
-        let pos = make_random_turn(&mut turns, rng);
-        if logged { println!("Making turn #{}: {} at [{}, {}]", turn, player_name(first), pos.0, pos.1); }
-        
-        board[pos] = if first {Mark::First} else {Mark::Second};
-        first = !first;
-        if logged { println!("{}", board); }
+/// The results returns as a vector of played games. The same seeded generator always
+/// produces the same sequence of games.
+pub fn simulate<R: Rng + ?Sized>(n: usize, rng: &mut R, logged: bool) -> Vec<Simulation> {
+    (0..n).map(|_| simulate_one(rng, logged)).collect()
+}
 
-        let status = board.status();
-        if logged { println!("{}", status_message(&status)); }
-        if status != Status::Ongoing { break; }
-    }
+/// Simulates a single game of random turns and returns the final state of a board with the turns made.
+pub fn simulate_one<R: Rng + ?Sized>(rng: &mut R, logged: bool) -> Simulation {
+    let mut random = Random::new(rng);
+    play_out(Board::new(), &mut |board, mark| random.choose(board, mark), logged)
+}
 
-    board
+/// Simulates a game between two strategies starting from the given board.
+pub fn simulate_match(board: Board, x: &mut dyn Strategy, o: &mut dyn Strategy, logged: bool) -> Simulation {
+    play_out(board, &mut |board, mark| match mark {
+        Mark::First => x.choose(board, mark),
+        _ => o.choose(board, mark),
+    }, logged)
 }
 
-fn make_random_turn(turns: &mut HashSet<Position>, rng: &mut ThreadRng) -> Position {
-    loop {
-        let i = rng.gen_range(0..SIDE_SIZE);
-        let j = rng.gen_range(0..SIDE_SIZE);
-        let pos = (i, j);
-        if turns.contains(&pos) { continue }
-        turns.insert(pos);
-        break pos
+/// Plays N games between strategies on the given number of threads and aggregates their statistics.
+///
+/// The games are split evenly between the threads, and each thread plays its share with the players
+/// `players` creates from a seed of its own. The seeds are drawn from a generator seeded with `seed`
+/// and the reports are merged in the order of the threads, so the same seed and number of threads
+/// always give the same report.
+pub fn simulate_parallel<F>(games: usize, threads: usize, seed: u64, players: F) -> SimulationReport
+where
+    F: Fn(u64) -> (Box<dyn Strategy>, Box<dyn Strategy>) + Sync,
+{
+    let threads = threads.max(1);
+    let mut seeds = StdRng::seed_from_u64(seed);
+    let seeds: Vec<u64> = (0..threads).map(|_| seeds.gen()).collect();
+    let players = &players;
+
+    let reports: Vec<SimulationReport> = thread::scope(|scope| {
+        let handles: Vec<_> = seeds.iter().enumerate().map(|(index, seed)| {
+            let share = games / threads + usize::from(index < games % threads);
+            scope.spawn(move || {
+                let (mut x, mut o) = players(*seed);
+                let mut report = SimulationReport::new();
+                for _ in 0..share {
+                    report.add(&simulate_match(Board::new(), x.as_mut(), o.as_mut(), false));
+                }
+                report
+            })
+        }).collect();
+        handles.into_iter().map(|handle| handle.join().expect("a simulation thread has panicked")).collect()
+    });
+
+    let mut total = SimulationReport::new();
+    for report in reports.iter() {
+        total.merge(report);
     }
+    total
+}
+
+/// Makes turns chosen by `choose` until the game is over.
+fn play_out(mut board: Board, choose: &mut dyn FnMut(&Board, Mark) -> Position, logged: bool) -> Simulation {
+    let mut moves = vec![];
+
+    while board.status() == Status::Ongoing {
+        let first = board.first_to_move();
+        let mark = Mark::of(first);
+        let pos = choose(&board, mark);
+        if logged { println!("Making turn #{}: {} at [{}, {}]", moves.len() + 1, player_name(first), pos.0, pos.1); }
+
+        board.set(pos, mark);
+        moves.push(pos);
+        if logged { println!("{}", board); }
+        if logged { println!("{}", status_message(&board.status())); }
+    }
+
+    Simulation {board, moves}
 }
 
 fn status_message(status: &Status) -> String {
@@ -57,14 +241,135 @@ fn status_message(status: &Status) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::strategy::{FirstFree, Heuristic, Perfect};
 
     #[test]
     fn test_simulation() {
-        let boards = simulate(10, false);
+        let boards = simulate(10, &mut thread_rng(), false);
 
-        let impossible: Vec<&Board> = boards.iter().filter(|x| x.impossible()).collect();
+        let impossible: Vec<&Simulation> = boards.iter().filter(|x| x.board.impossible()).collect();
 
         assert!(boards.len() == 10);
-        assert!(impossible.len() == 0);
+        assert!(impossible.is_empty());
+    }
+
+    #[test]
+    fn test_seeded_simulation_is_reproducible() {
+        let run = |seed| -> Vec<String> {
+            let boards = simulate(20, &mut StdRng::seed_from_u64(seed), false);
+            boards.iter().map(|x| format!("{}", x.board)).collect()
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn test_simulated_match() {
+        let perfect = simulate_match(Board::new(), &mut Perfect::new(), &mut Perfect::new(), false);
+        let heuristic = simulate_match(Board::new(), &mut FirstFree, &mut Heuristic, false);
+
+        assert_eq!(perfect.board.status(), Status::Tie);
+        assert_eq!(perfect.moves.len(), 9);
+        assert_eq!(heuristic.board.status(), Status::Winner(false));
+        assert_eq!(heuristic.moves, vec![(0, 0), (1, 1), (0, 1), (0, 2), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn test_simulated_match_from_position() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        let game = simulate_match(board, &mut FirstFree, &mut FirstFree, false);
+
+        assert_eq!(game.moves, vec![(0, 2)]);
+        assert_eq!(game.board.status(), Status::Winner(true));
+    }
+
+    #[test]
+    fn test_report() {
+        let games = [
+            game(vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]),
+            game(vec![(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (1, 2)]),
+            game(vec![(1, 1), (0, 0), (2, 2), (0, 2), (0, 1), (2, 1), (1, 0), (1, 2), (2, 0)]),
+        ];
+
+        let report: SimulationReport = games.iter().collect();
+
+        assert_eq!(report.total, Tally {x_wins: 1, o_wins: 1, ties: 1});
+        assert_eq!(report.lengths, BTreeMap::from([(5, 1), (6, 1), (9, 1)]));
+        assert_eq!(report.openings, BTreeMap::from([
+            ((0, 0), Tally {x_wins: 1, o_wins: 1, ties: 0}),
+            ((1, 1), Tally {x_wins: 0, o_wins: 0, ties: 1}),
+        ]));
+    }
+
+    #[test]
+    fn test_merged_reports_match_the_whole() {
+        let games = simulate(200, &mut StdRng::seed_from_u64(1), false);
+
+        let mut merged: SimulationReport = games[..50].iter().collect();
+        merged.merge(&games[50..].iter().collect());
+
+        assert_eq!(merged, games.iter().collect());
+        assert_eq!(merged.total.games(), 200);
+        assert_eq!(merged.lengths.values().sum::<usize>(), 200);
+        assert_eq!(merged.openings.values().map(Tally::games).sum::<usize>(), 200);
+    }
+
+    #[test]
+    fn test_parallel_simulation_is_reproducible() {
+        let run = |seed, threads| simulate_parallel(1001, threads, seed, random_players);
+
+        let report = run(42, 4);
+
+        assert_eq!(report.total.games(), 1001);
+        assert_eq!(report, run(42, 4));
+        assert_ne!(report, run(43, 4));
+        assert_eq!(run(42, 3).total.games(), 1001);
+    }
+
+    #[test]
+    fn test_parallel_simulation_with_more_threads_than_games() {
+        let report = simulate_parallel(3, 8, 0, |_| (Box::new(FirstFree), Box::new(FirstFree)));
+
+        assert_eq!(report.total, Tally {x_wins: 3, o_wins: 0, ties: 0});
+        assert_eq!(report.lengths, BTreeMap::from([(7, 3)]));
+    }
+
+    #[test]
+    fn test_structured_output() {
+        let won = game(vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+        let tied = game(vec![(1, 1), (0, 0), (2, 2), (0, 2), (0, 1), (2, 1), (1, 0), (1, 2), (2, 0)]);
+
+        assert_eq!(
+            won.json(1),
+            r#"{"game":1,"moves":[[0,0],[1,0],[0,1],[1,1],[0,2]],"board":"xxx|oo.|...","status":"x_wins","plies":5}"#,
+        );
+        assert_eq!(won.csv(1), r#"1,"0,0 1,0 0,1 1,1 0,2",xxx|oo.|...,x_wins,5"#);
+        assert_eq!(tied.csv(2), r#"2,"1,1 0,0 2,2 0,2 0,1 2,1 1,0 1,2 2,0",oxo|xxo|xox,tie,9"#);
+        assert_eq!(CSV_HEADER.split(',').count(), 5);
+    }
+
+    #[test]
+    fn test_structured_board_is_parsed_back() {
+        let simulation = simulate_one(&mut StdRng::seed_from_u64(7), false);
+
+        let csv = simulation.csv(1);
+        let board = csv.split(',').nth_back(2).unwrap();
+
+        assert_eq!(Board::try_from(board).unwrap(), simulation.board);
+    }
+
+    fn random_players(seed: u64) -> (Box<dyn Strategy>, Box<dyn Strategy>) {
+        (Box::new(Random::new(StdRng::seed_from_u64(seed))), Box::new(Random::new(StdRng::seed_from_u64(seed + 1))))
     }
-}
\ No newline at end of file
+
+    /// The game of the turns made on the classic board, X first.
+    fn game(moves: Vec<Position>) -> Simulation {
+        let mut board = Board::new();
+        for (i, pos) in moves.iter().enumerate() {
+            board.set(*pos, Mark::of(i % 2 == 0));
+        }
+        Simulation {board, moves}
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::board::{Board, BoardIterator, Mark, Position, Status};
+
+const WIN_SCORE: i32 = 1000;
+/// How many positions a search visits between looking at the clock.
+const DEADLINE_CHECK: usize = 1024;
+
+/// The game-theoretic value of a board from the point of view of the player making the next turn.
+///
+/// Wins and losses carry the number of turns (plies) until the game ends, assuming both players
+/// play perfectly: the winner tries to finish as soon as possible, and the loser tries to hold on
+/// as long as possible.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Win(usize),
+    Loss(usize),
+    Draw,
+}
+
+/// Search results cached by position, which can be reused across searches.
+///
+/// Scores are stored relative to the position itself rather than the root of the search, so an
+/// entry stays valid whatever the number of turns that led to the position.
+#[derive(Clone, Debug, Default)]
+pub struct TranspositionTable {
+    entries: HashMap<Board, Entry>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Entry {
+    score: i32,
+    bound: Bound,
+}
+
+/// How the stored score relates to the true one after an alpha-beta cutoff.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self { Self::default() }
+
+    /// The number of cached positions.
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    pub fn clear(&mut self) { self.entries.clear() }
+
+    fn get(&self, board: &Board, ply: i32) -> Option<(i32, Bound)> {
+        self.entries.get(board).map(|entry| (from_node(entry.score, ply), entry.bound))
+    }
+
+    fn insert(&mut self, board: &Board, ply: i32, score: i32, bound: Bound) {
+        self.entries.insert(board.clone(), Entry {score: to_node(score, ply), bound});
+    }
+}
+
+/// The best turn found by a search and the value of the position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// The best turn, or `None` if the game is over.
+    pub best: Option<Position>,
+    pub outcome: Outcome,
+    /// The number of positions visited.
+    pub nodes: usize,
+}
+
+/// Evaluates the board assuming perfect play from both sides.
+///
+/// Panics if the board is in an impossible state.
+pub fn evaluate(board: &Board) -> Outcome {
+    evaluate_with(board, &mut TranspositionTable::new())
+}
+
+/// Same as `evaluate`, but reuses and extends the results cached in the table.
+pub fn evaluate_with(board: &Board, table: &mut TranspositionTable) -> Outcome {
+    search(board, table, None).expect("no deadline to miss").outcome
+}
+
+/// Returns the best turn for the player making the next turn, or `None` if the game is over.
+///
+/// Among equally good turns, the first one in the board's iteration order is picked.
+pub fn best_move(board: &Board) -> Option<Position> {
+    best_move_with(board, &mut TranspositionTable::new())
+}
+
+/// Same as `best_move`, but reuses and extends the results cached in the table.
+pub fn best_move_with(board: &Board, table: &mut TranspositionTable) -> Option<Position> {
+    search(board, table, None).expect("no deadline to miss").best
+}
+
+/// Solves the board, giving up if the deadline passes first.
+///
+/// An unfinished search returns `None` and leaves in the table only the results it has completed.
+/// Panics if the board is in an impossible state.
+pub fn search(board: &Board, table: &mut TranspositionTable, deadline: Option<Instant>) -> Option<SearchResult> {
+    let mut search = Search {table, deadline, nodes: 0, timed_out: false};
+    let mut board = board.clone();
+    if board.status() != Status::Ongoing {
+        let score = search.negamax(&mut board, 0, -WIN_SCORE, WIN_SCORE);
+        return Some(SearchResult {best: None, outcome: outcome(score), nodes: search.nodes});
+    }
+
+    let mark = next_mark(&board);
+    let mut best: Option<(Position, i32)> = None;
+    let mut alpha = -WIN_SCORE;
+    for pos in free_cells(&board) {
+        board.set(pos, mark);
+        let score = -search.negamax(&mut board, 1, -WIN_SCORE, -alpha);
+        board.set(pos, Mark::Empty);
+        if search.timed_out {
+            return None;
+        }
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((pos, score));
+            alpha = alpha.max(score);
+        }
+    }
+    best.map(|(pos, score)| SearchResult {best: Some(pos), outcome: outcome(score), nodes: search.nodes})
+}
+
+/// The state of a single search: where it caches results and when it has to stop.
+struct Search<'a> {
+    table: &'a mut TranspositionTable,
+    deadline: Option<Instant>,
+    nodes: usize,
+    timed_out: bool,
+}
+
+impl Search<'_> {
+    /// Scores the board for the player making the next turn with alpha-beta pruning.
+    ///
+    /// The score is positive for a win, negative for a loss, and zero for a draw. Its absolute value
+    /// decreases with the ply at which the game ends, so faster wins score higher.
+    fn negamax(&mut self, board: &mut Board, ply: i32, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(DEADLINE_CHECK) && self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.timed_out = true;
+        }
+        if self.timed_out {
+            return 0;
+        }
+        match board.status() {
+            Status::Impossible => panic!("cannot evaluate impossible board state:\n{}", board),
+            Status::Tie => return 0,
+            Status::Winner(first) => {
+                return if first == board.first_to_move() { WIN_SCORE - ply } else { ply - WIN_SCORE };
+            }
+            Status::Ongoing => {}
+        }
+        if let Some((score, bound)) = self.table.get(board, ply) {
+            match bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return score;
+            }
+        }
+        let original_alpha = alpha;
+        let mark = next_mark(board);
+        let mut best = -WIN_SCORE;
+        for pos in free_cells(board) {
+            board.set(pos, mark);
+            let score = -self.negamax(board, ply + 1, -beta, -alpha);
+            board.set(pos, Mark::Empty);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta || self.timed_out {
+                break;
+            }
+        }
+        if self.timed_out {
+            return 0;
+        }
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(board, ply, best, bound);
+        best
+    }
+}
+
+/// Converts a score counted from the root of the search into one counted from the position at `ply`.
+fn to_node(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > 0 => s + ply,
+        s if s < 0 => s - ply,
+        _ => 0,
+    }
+}
+
+fn from_node(score: i32, ply: i32) -> i32 {
+    match score {
+        s if s > 0 => s - ply,
+        s if s < 0 => s + ply,
+        _ => 0,
+    }
+}
+
+fn outcome(score: i32) -> Outcome {
+    match score {
+        0 => Outcome::Draw,
+        s if s > 0 => Outcome::Win((WIN_SCORE - s) as usize),
+        s => Outcome::Loss((WIN_SCORE + s) as usize),
+    }
+}
+
+fn next_mark(board: &Board) -> Mark {
+    Mark::of(board.first_to_move())
+}
+
+fn free_cells(board: &Board) -> Vec<Position> {
+    BoardIterator::new(board)
+        .filter(|(_, mark)| *mark == Mark::Empty)
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_board_is_a_draw() {
+        assert_eq!(evaluate(&Board::new()), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_finished_game() {
+        let board = Board::try_from("xxx|oo.|...").unwrap();
+
+        assert_eq!(evaluate(&board), Outcome::Loss(0));
+        assert_eq!(best_move(&board), None);
+    }
+
+    #[test]
+    fn test_takes_immediate_win() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        assert_eq!(evaluate(&board), Outcome::Win(1));
+        assert_eq!(best_move(&board), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_blocks_opponent() {
+        let board = Board::try_from("xx.|.o.|...").unwrap();
+
+        assert_eq!(evaluate(&board), Outcome::Draw);
+        assert_eq!(best_move(&board), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_fork_loses_in_three_plies() {
+        let board = Board::try_from("x..|.o.|..x").unwrap();
+        let mut lost = Board::try_from("x..|.o.|..x").unwrap();
+        lost.set((0, 2), Mark::Second);
+
+        assert_eq!(evaluate(&board), Outcome::Draw);
+        assert_eq!(evaluate(&lost), Outcome::Win(3));
+    }
+
+    #[test]
+    fn test_table_is_reused_across_calls() {
+        let mut table = TranspositionTable::new();
+
+        assert_eq!(evaluate_with(&Board::new(), &mut table), Outcome::Draw);
+        let cached = table.len();
+        assert!(cached > 0);
+        let board = Board::try_from("x..|.o.|..x").unwrap();
+        assert_eq!(evaluate_with(&board, &mut table), Outcome::Draw);
+        assert_eq!(best_move_with(&board, &mut table), best_move(&board));
+        assert!(table.len() >= cached);
+    }
+
+    #[test]
+    fn test_same_results_with_and_without_table() {
+        let mut table = TranspositionTable::new();
+        for notation in ["...|...|...", "x..|...|...", "x..|.o.|..x", "xx.|.o.|...", "xo.|.x.|..o", "x.o|...|..."] {
+            let board = Board::try_from(notation).unwrap();
+
+            assert_eq!(evaluate_with(&board, &mut table), evaluate(&board));
+            assert_eq!(best_move_with(&board, &mut table), best_move(&board));
+        }
+    }
+
+    #[test]
+    fn test_search_reports_the_outcome() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        let result = search(&board, &mut TranspositionTable::new(), None).unwrap();
+
+        assert_eq!(result.best, Some((0, 2)));
+        assert_eq!(result.outcome, Outcome::Win(1));
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn test_search_gives_up_after_deadline() {
+        let mut table = TranspositionTable::new();
+
+        let result = search(&Board::with_size(4, 4, 4), &mut table, Some(Instant::now()));
+
+        assert_eq!(result, None);
+        assert_eq!(evaluate_with(&Board::try_from("xxx|oo.|...").unwrap(), &mut table), Outcome::Loss(0));
+    }
+}
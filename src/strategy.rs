@@ -0,0 +1,170 @@
+use rand::prelude::*;
+
+use crate::board::{Board, BoardIterator, Mark, Position};
+use crate::solver::{best_move_with, TranspositionTable};
+
+/// A way to choose the next turn.
+///
+/// Strategies are asked only for boards where the game is ongoing and `me` makes the next turn.
+pub trait Strategy {
+    fn choose(&mut self, board: &Board, me: Mark) -> Position;
+}
+
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+    fn choose(&mut self, board: &Board, me: Mark) -> Position {
+        (**self).choose(board, me)
+    }
+}
+
+/// Picks any of the free cells with equal probability.
+pub struct Random<R> {
+    rng: R,
+}
+
+impl<R: Rng> Random<R> {
+    pub fn new(rng: R) -> Self { Self {rng} }
+}
+
+impl<R: Rng> Strategy for Random<R> {
+    fn choose(&mut self, board: &Board, _me: Mark) -> Position {
+        *free_cells(board).choose(&mut self.rng).expect("no free cells left")
+    }
+}
+
+/// Picks the first free cell going row by row.
+pub struct FirstFree;
+
+impl Strategy for FirstFree {
+    fn choose(&mut self, board: &Board, _me: Mark) -> Position {
+        free_cells(board).into_iter().next().expect("no free cells left")
+    }
+}
+
+/// Completes its own line if it can, otherwise blocks the opponent's line.
+///
+/// Without lines to complete or block, prefers the center, then the corners, then the first free cell.
+pub struct Heuristic;
+
+impl Strategy for Heuristic {
+    fn choose(&mut self, board: &Board, me: Mark) -> Position {
+        let free = free_cells(board);
+        let opponent = if me == Mark::First { Mark::Second } else { Mark::First };
+        let (rows, cols) = (board.rows(), board.cols());
+        let preferred = [
+            (rows / 2, cols / 2), (0, 0), (0, cols - 1), (rows - 1, 0), (rows - 1, cols - 1),
+        ];
+
+        free.iter().find(|pos| completes_line(board, **pos, me))
+            .or_else(|| free.iter().find(|pos| completes_line(board, **pos, opponent)))
+            .or_else(|| preferred.iter().find(|pos| board.empty(pos)))
+            .or_else(|| free.first())
+            .copied()
+            .expect("no free cells left")
+    }
+}
+
+/// Never loses: plays the best turn found by the solver, remembering the positions it has searched.
+#[derive(Default)]
+pub struct Perfect {
+    table: TranspositionTable,
+}
+
+impl Perfect {
+    pub fn new() -> Self { Self::default() }
+}
+
+impl Strategy for Perfect {
+    fn choose(&mut self, board: &Board, _me: Mark) -> Position {
+        best_move_with(board, &mut self.table).expect("no free cells left")
+    }
+}
+
+/// Creates a strategy by its name: `random`, `first`, `heuristic`, or `perfect`.
+///
+/// The random strategy draws its turns from a generator with the given seed.
+pub fn from_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(Random::new(StdRng::seed_from_u64(seed)))),
+        "first" => Some(Box::new(FirstFree)),
+        "heuristic" => Some(Box::new(Heuristic)),
+        "perfect" => Some(Box::new(Perfect::new())),
+        _ => None,
+    }
+}
+
+fn free_cells(board: &Board) -> Vec<Position> {
+    BoardIterator::new(board)
+        .filter(|(_, mark)| *mark == Mark::Empty)
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+fn completes_line(board: &Board, pos: Position, mark: Mark) -> bool {
+    let mut board = board.clone();
+    board.set(pos, mark);
+    board.winning_line().is_some_and(|line| line.contains(&pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_picks_free_cells_reproducibly() {
+        let board = Board::try_from("xo.|.x.|o..").unwrap();
+        let choices = |seed| -> Vec<Position> {
+            let mut random = Random::new(StdRng::seed_from_u64(seed));
+            (0..20).map(|_| random.choose(&board, Mark::First)).collect()
+        };
+
+        assert!(choices(1).iter().all(|pos| board.empty(pos)));
+        assert_eq!(choices(1), choices(1));
+    }
+
+    #[test]
+    fn test_first_free() {
+        let board = Board::try_from("xo.|.x.|o..").unwrap();
+
+        assert_eq!(FirstFree.choose(&board, Mark::Second), (0, 2));
+    }
+
+    #[test]
+    fn test_heuristic_wins_before_blocking() {
+        let board = Board::try_from("oo.|xx.|x..").unwrap();
+
+        assert_eq!(Heuristic.choose(&board, Mark::Second), (0, 2));
+    }
+
+    #[test]
+    fn test_heuristic_blocks() {
+        let board = Board::try_from("xx.|.o.|...").unwrap();
+
+        assert_eq!(Heuristic.choose(&board, Mark::Second), (0, 2));
+    }
+
+    #[test]
+    fn test_heuristic_prefers_center_and_corners() {
+        let empty = Board::new();
+        let center = Board::try_from("...|.x.|...").unwrap();
+
+        assert_eq!(Heuristic.choose(&empty, Mark::First), (1, 1));
+        assert_eq!(Heuristic.choose(&center, Mark::Second), (0, 0));
+    }
+
+    #[test]
+    fn test_perfect_takes_win() {
+        let board = Board::try_from("xx.|oo.|...").unwrap();
+
+        assert_eq!(Perfect::new().choose(&board, Mark::First), (0, 2));
+    }
+
+    #[test]
+    fn test_strategy_names() {
+        for name in ["random", "first", "heuristic", "perfect"] {
+            let mut strategy = from_name(name, 0).unwrap();
+
+            assert!(Board::new().empty(&strategy.choose(&Board::new(), Mark::First)));
+        }
+        assert!(from_name("clairvoyant", 0).is_none());
+    }
+}
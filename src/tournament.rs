@@ -0,0 +1,232 @@
+use std::fmt;
+
+use crate::board::{Board, Status};
+use crate::simulator::simulate_match;
+use crate::strategy::Strategy;
+
+/// The rating every participant starts with.
+pub const INITIAL_RATING: f64 = 1500.0;
+/// How much a single game can change a rating.
+pub const K_FACTOR: f64 = 16.0;
+
+/// Results of one participant's games against another one.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Score {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+}
+
+impl Score {
+    pub fn games(&self) -> usize { self.wins + self.losses + self.draws }
+
+    /// The number of points scored: one per win and a half per draw.
+    pub fn points(&self) -> f64 { self.wins as f64 + 0.5 * self.draws as f64 }
+
+    fn merge(&mut self, other: &Score) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.draws += other.draws;
+    }
+}
+
+/// A strategy taking part in a tournament under a name.
+pub struct Participant {
+    pub name: String,
+    pub strategy: Box<dyn Strategy>,
+}
+
+impl Participant {
+    pub fn new(name: &str, strategy: Box<dyn Strategy>) -> Self {
+        Self {name: String::from(name), strategy}
+    }
+}
+
+/// Results of a round-robin tournament.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TournamentReport {
+    pub names: Vec<String>,
+    /// The score of the i-th participant against the j-th one.
+    pub crosstable: Vec<Vec<Score>>,
+    /// Elo-style ratings updated after every game.
+    pub ratings: Vec<f64>,
+}
+
+impl TournamentReport {
+    /// The score of the participant against everyone else.
+    pub fn total(&self, i: usize) -> Score {
+        let mut total = Score::default();
+        for score in self.crosstable[i].iter() {
+            total.merge(score);
+        }
+        total
+    }
+
+    fn record(&mut self, x: usize, o: usize, status: Status) {
+        let x_points = match status {
+            Status::Winner(true) => {
+                self.crosstable[x][o].wins += 1;
+                self.crosstable[o][x].losses += 1;
+                1.0
+            }
+            Status::Winner(false) => {
+                self.crosstable[x][o].losses += 1;
+                self.crosstable[o][x].wins += 1;
+                0.0
+            }
+            _ => {
+                self.crosstable[x][o].draws += 1;
+                self.crosstable[o][x].draws += 1;
+                0.5
+            }
+        };
+
+        let expected = 1.0 / (1.0 + 10f64.powf((self.ratings[o] - self.ratings[x]) / 400.0));
+        let change = K_FACTOR * (x_points - expected);
+        self.ratings[x] += change;
+        self.ratings[o] -= change;
+    }
+}
+
+/// Plays every pair of participants against each other for N games starting from the given board.
+///
+/// The games are played in rounds, one game for each pair per round, and the participants of a
+/// pair take turns to move first.
+pub fn round_robin(participants: &mut [Participant], games: usize, board: &Board) -> TournamentReport {
+    let n = participants.len();
+    let mut report = TournamentReport {
+        names: participants.iter().map(|p| p.name.clone()).collect(),
+        crosstable: vec![vec![Score::default(); n]; n],
+        ratings: vec![INITIAL_RATING; n],
+    };
+
+    for round in 0..games {
+        for i in 0..n {
+            for j in i + 1..n {
+                let (left, right) = participants.split_at_mut(j);
+                let (fst, snd) = (left[i].strategy.as_mut(), right[0].strategy.as_mut());
+                let game = if round % 2 == 0 {
+                    simulate_match(board.clone(), fst, snd, false)
+                } else {
+                    simulate_match(board.clone(), snd, fst, false)
+                };
+                let (x, o) = if round % 2 == 0 { (i, j) } else { (j, i) };
+                report.record(x, o, game.board.status());
+            }
+        }
+    }
+
+    report
+}
+
+impl fmt::Display for TournamentReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.names.iter().map(|name| name.len()).max().unwrap_or(0).max(10);
+
+        writeln!(formatter, "Crosstable (wins/losses/draws of the row against the column):")?;
+        write!(formatter, "{:<width$}", "", width = width)?;
+        for name in self.names.iter() {
+            write!(formatter, " {:>width$}", name, width = width)?;
+        }
+        writeln!(formatter)?;
+        for (i, (name, scores)) in self.names.iter().zip(self.crosstable.iter()).enumerate() {
+            write!(formatter, "{:<width$}", name, width = width)?;
+            for (j, score) in scores.iter().enumerate() {
+                let cell = if i == j {
+                    String::from("-")
+                } else {
+                    format!("{}/{}/{}", score.wins, score.losses, score.draws)
+                };
+                write!(formatter, " {:>width$}", cell, width = width)?;
+            }
+            writeln!(formatter)?;
+        }
+
+        writeln!(formatter, "\nStandings:")?;
+        writeln!(
+            formatter, "{:<width$} {:>7} {:>7} {:>7} {:>7} {:>7}",
+            "", "Wins", "Losses", "Draws", "Points", "Elo", width = width,
+        )?;
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|a, b| self.ratings[*b].total_cmp(&self.ratings[*a]));
+        for i in order {
+            let total = self.total(i);
+            writeln!(
+                formatter, "{:<width$} {:>7} {:>7} {:>7} {:>7.1} {:>7.0}",
+                self.names[i], total.wins, total.losses, total.draws, total.points(), self.ratings[i],
+                width = width,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::strategy::{FirstFree, Perfect, Random};
+
+    fn participants(seed: u64) -> Vec<Participant> {
+        vec![
+            Participant::new("random", Box::new(Random::new(StdRng::seed_from_u64(seed)))),
+            Participant::new("first", Box::new(FirstFree)),
+            Participant::new("perfect", Box::new(Perfect::new())),
+        ]
+    }
+
+    #[test]
+    fn test_every_pair_plays_n_games() {
+        let report = round_robin(&mut participants(0), 6, &Board::new());
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 0 } else { 6 };
+                assert_eq!(report.crosstable[i][j].games(), expected);
+            }
+            assert_eq!(report.total(i).games(), 12);
+        }
+    }
+
+    #[test]
+    fn test_crosstable_is_symmetric() {
+        let report = round_robin(&mut participants(0), 6, &Board::new());
+
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(report.crosstable[i][j].wins, report.crosstable[j][i].losses);
+                assert_eq!(report.crosstable[i][j].draws, report.crosstable[j][i].draws);
+            }
+        }
+    }
+
+    #[test]
+    fn test_perfect_play_is_rated_highest() {
+        let report = round_robin(&mut participants(0), 10, &Board::new());
+
+        assert_eq!(report.total(2).losses, 0);
+        assert!(report.ratings[2] > report.ratings[0]);
+        assert!(report.ratings[2] > report.ratings[1]);
+        assert!((report.ratings.iter().sum::<f64>() - 3.0 * INITIAL_RATING).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_first_player_alternates() {
+        let mut participants = vec![
+            Participant::new("a", Box::new(FirstFree)),
+            Participant::new("b", Box::new(FirstFree)),
+        ];
+
+        let report = round_robin(&mut participants, 4, &Board::new());
+
+        assert_eq!(report.crosstable[0][1], Score {wins: 2, losses: 2, draws: 0});
+    }
+
+    #[test]
+    fn test_seeded_tournament_is_reproducible() {
+        let first = round_robin(&mut participants(3), 5, &Board::new());
+        let second = round_robin(&mut participants(3), 5, &Board::new());
+
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,97 @@
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tictactoe::arena::{run_match, EngineProcess, Forfeit, Limits};
+use tictactoe::board::{Board, Status};
+
+const ENGINE: &str = env!("CARGO_BIN_EXE_engine");
+
+fn engines(x: &str, o: &str) -> [EngineProcess; 2] {
+    [EngineProcess::spawn(x).unwrap(), EngineProcess::spawn(o).unwrap()]
+}
+
+/// The command of the stub engine, an example that Cargo builds once for all the tests.
+fn stub(behavior: &str) -> String {
+    static PATH: OnceLock<String> = OnceLock::new();
+    format!("{} {}", PATH.get_or_init(build_stub), behavior)
+}
+
+/// Builds the stub engine and returns the path of the executable that Cargo reports.
+fn build_stub() -> String {
+    let output = Command::new(env!("CARGO"))
+        .args(["build", "--example", "stub-engine", "--message-format=json"])
+        .output()
+        .expect("cargo runs");
+    assert!(output.status.success(), "cannot build the stub engine: {}", String::from_utf8_lossy(&output.stderr));
+    let messages = String::from_utf8(output.stdout).unwrap();
+    let message = messages.lines()
+        .find(|line| line.contains(r#""kind":["example"]"#) && line.contains(r#""name":"stub-engine""#))
+        .expect("cargo reports the stub engine");
+    let (_, path) = message.split_once(r#""executable":""#).expect("the stub engine is an executable");
+    String::from(&path[..path.find('"').unwrap()])
+}
+
+fn limits(timeout: u64) -> Limits {
+    Limits {movetime: Duration::from_millis(100), timeout: Duration::from_millis(timeout)}
+}
+
+#[test]
+fn test_perfect_engines_draw() {
+    let report = run_match(&mut engines(ENGINE, ENGINE), 2, &Board::new(), &limits(5000));
+
+    assert_eq!(report.scores[0].draws, 2);
+    assert!(report.games.iter().all(|game| game.status == Status::Tie && game.moves.len() == 9));
+    assert_eq!((report.games[0].x, report.games[1].x), (0, 1));
+}
+
+#[test]
+fn test_perfect_engine_beats_first_free_cell() {
+    let report = run_match(&mut engines(ENGINE, &stub("first")), 2, &Board::new(), &limits(5000));
+
+    assert_eq!(report.scores[0].wins, 2);
+    assert!(report.games.iter().all(|game| game.forfeit.is_none()));
+}
+
+#[test]
+fn test_illegal_moves_forfeit() {
+    let report = run_match(&mut engines(&stub("first"), &stub("illegal")), 2, &Board::new(), &limits(5000));
+
+    assert_eq!(report.scores[0].wins, 2);
+    assert_eq!(report.games[0].forfeit, Some((false, Forfeit::IllegalMove(String::from("0,0")))));
+    assert_eq!(report.games[1].forfeit, Some((true, Forfeit::IllegalMove(String::from("0,0")))));
+    assert_eq!(report.games[1].moves.len(), 2);
+}
+
+#[test]
+fn test_garbage_answer_forfeits() {
+    let report = run_match(&mut engines(&stub("garbage"), &stub("first")), 1, &Board::new(), &limits(5000));
+
+    assert_eq!(report.games[0].status, Status::Winner(false));
+    assert_eq!(report.games[0].forfeit, Some((true, Forfeit::IllegalMove(String::from("somewhere")))));
+}
+
+#[test]
+fn test_timeouts_and_crashes_forfeit() {
+    let report = run_match(&mut engines(&stub("silent"), &stub("crash")), 3, &Board::new(), &limits(300));
+
+    assert_eq!(report.games[0].forfeit, Some((true, Forfeit::Timeout)));
+    assert_eq!(report.games[1].forfeit, Some((true, Forfeit::Crashed)));
+    assert_eq!(report.games[2].forfeit, Some((true, Forfeit::Timeout)));
+}
+
+#[test]
+fn test_summary() {
+    let report = run_match(&mut engines(&stub("first"), &stub("illegal")), 1, &Board::new(), &limits(5000));
+
+    let summary = report.to_string();
+
+    assert!(summary.starts_with(&format!("Game 1: {} (X) vs {} (O): 1-0", stub("first"), stub("illegal"))));
+    assert!(summary.contains("O forfeits: illegal move: 0,0"));
+}
+
+#[test]
+fn test_missing_engine() {
+    assert!(EngineProcess::spawn("./no-such-engine").is_err());
+    assert!(EngineProcess::spawn("").is_err());
+}
@@ -0,0 +1,234 @@
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use tictactoe::interactive::lobby::serve;
+
+/// Starts a lobby on a free local port.
+fn lobby() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener));
+    address
+}
+
+/// A client speaking the lobby's protocol line by line.
+struct Client {
+    stream: TcpStream,
+    lines: Lines<BufReader<TcpStream>>,
+}
+
+impl Client {
+    /// Connects with the given name.
+    fn connect(address: SocketAddr, name: &str) -> Self {
+        let stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        stream.set_write_timeout(Some(Duration::from_secs(5))).unwrap();
+        stream.set_nodelay(true).unwrap();
+        let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+        let mut client = Self {stream, lines};
+        assert!(client.next().starts_with("hello guest"));
+        client.send(&format!("name {}", name));
+        assert_eq!(client.next(), format!("hello {}", name));
+        client
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stream, "{}", line).unwrap();
+    }
+
+    fn next(&mut self) -> String {
+        self.lines.next().expect("the lobby has disconnected").unwrap()
+    }
+
+    /// Skips the lines until one that starts with the prefix.
+    fn until(&mut self, prefix: &str) -> String {
+        loop {
+            let line = self.next();
+            if line.starts_with(prefix) {
+                return line;
+            }
+        }
+    }
+
+    /// Makes the turn when asked for it.
+    fn turn(&mut self, turn: &str) {
+        self.until("move");
+        self.send(turn);
+    }
+
+    fn list(&mut self) -> Vec<String> {
+        self.send("list");
+        let mut games = vec![];
+        loop {
+            match self.next() {
+                line if line == "end" => return games,
+                line => games.push(line),
+            }
+        }
+    }
+}
+
+/// Plays the turns of both players in order, X first.
+fn play(x: &mut Client, o: &mut Client, turns: &[&str]) {
+    for (i, turn) in turns.iter().enumerate() {
+        if i % 2 == 0 { x.turn(turn) } else { o.turn(turn) }
+    }
+}
+
+#[test]
+fn test_game_with_spectator() {
+    let address = lobby();
+    let mut alice = Client::connect(address, "alice");
+    let mut bob = Client::connect(address, "bob");
+    let mut carol = Client::connect(address, "carol");
+
+    alice.send("create");
+    assert_eq!(alice.next(), "created 1");
+    assert_eq!(bob.list(), vec!["game 1 open 3x3 3 alice - 0"]);
+    carol.send("watch 1");
+    assert_eq!(carol.next(), "watching 1");
+    bob.send("join 1");
+    assert_eq!(bob.next(), "joined 1 O");
+    assert_eq!(alice.next(), "joined 1 X");
+    assert_eq!(carol.next(), "board 3 ...|...|...");
+    assert_eq!(carol.list(), vec!["game 1 playing 3x3 3 alice bob 1"]);
+
+    play(&mut alice, &mut bob, &["0,0", "1,1", "0,1", "1,0", "0,2"]);
+
+    assert_eq!(carol.until("gameover"), "gameover 3 xxx|oo.|... the player X wins!");
+    assert_eq!(carol.next(), "result 1 1-0 X wins");
+    assert_eq!(bob.until("result"), "result 1 1-0 X wins");
+    assert_eq!(alice.until("result"), "result 1 1-0 X wins");
+    assert!(alice.list().is_empty());
+}
+
+#[test]
+fn test_concurrent_games() {
+    let address = lobby();
+    let mut players: Vec<Client> = ["a", "b", "c", "d"].iter().map(|name| Client::connect(address, name)).collect();
+
+    players[0].send("create 4x4 3");
+    assert_eq!(players[0].next(), "created 1");
+    players[2].send("create");
+    assert_eq!(players[2].next(), "created 2");
+    players[1].send("join 1");
+    players[3].send("join 2");
+    assert_eq!(players[1].next(), "joined 1 O");
+    assert_eq!(players[3].next(), "joined 2 O");
+
+    // The turns of both games are interleaved.
+    let games = [["1,1", "0,0", "1,2", "0,1", "1,3"], ["0,0", "1,1", "0,1", "2,2", "0,2"]];
+    for i in 0..5 {
+        for (game, turns) in games.iter().enumerate() {
+            players[game*2 + i % 2].turn(turns[i]);
+        }
+    }
+
+    assert_eq!(players[3].until("result"), "result 2 1-0 X wins");
+    assert_eq!(players[1].until("gameover"), "gameover 3 oo..|.xxx|....|.... the player X wins!");
+    assert_eq!(players[1].next(), "result 1 1-0 X wins");
+}
+
+#[test]
+fn test_chat() {
+    let address = lobby();
+    let mut alice = Client::connect(address, "alice");
+    let mut bob = Client::connect(address, "bob");
+    let mut carol = Client::connect(address, "carol");
+
+    alice.send("create");
+    alice.next();
+    bob.send("join 1");
+    bob.next();
+    alice.send("say good luck");
+    carol.send("say anyone?");
+
+    assert_eq!(bob.until("chat"), "chat alice good luck");
+    assert_eq!(alice.until("chat"), "chat alice good luck");
+    // Chat in the lobby does not reach the players.
+    assert_eq!(carol.next(), "chat carol anyone?");
+    bob.send("say thanks");
+    assert_eq!(alice.until("chat"), "chat bob thanks");
+}
+
+#[test]
+fn test_disconnect_forfeits() {
+    let address = lobby();
+    let mut alice = Client::connect(address, "alice");
+    let mut bob = Client::connect(address, "bob");
+
+    alice.send("create");
+    alice.next();
+    bob.send("join 1");
+    play(&mut alice, &mut bob, &["1,1", "0,0"]);
+    alice.until("move");
+    drop(alice);
+
+    assert_eq!(bob.until("result"), "result 1 0-1 X forfeits");
+    assert!(bob.list().is_empty());
+}
+
+#[test]
+fn test_leaving_open_game_closes_it() {
+    let address = lobby();
+    let mut alice = Client::connect(address, "alice");
+    let mut bob = Client::connect(address, "bob");
+
+    alice.send("create 5x5 4");
+    alice.next();
+    bob.send("watch 1");
+    bob.next();
+    alice.send("leave");
+
+    assert_eq!(alice.next(), "left 1");
+    assert_eq!(bob.next(), "left 1");
+    assert!(bob.list().is_empty());
+}
+
+#[test]
+fn test_errors() {
+    let address = lobby();
+    let mut alice = Client::connect(address, "alice");
+    let mut bob = Client::connect(address, "bob");
+
+    bob.send("name alice");
+    assert_eq!(bob.next(), "error the name alice is taken");
+    bob.send("join 7");
+    assert_eq!(bob.next(), "error no such game: 7");
+    bob.send("create 3x3 4");
+    assert_eq!(bob.next(), "error cannot make 4 in a row on a 3x3 board");
+    bob.send("create 5x100000000 1");
+    assert_eq!(bob.next(), "error the board can be at most 12x12");
+    bob.send("create 99999999999x99999999999 1");
+    assert_eq!(bob.next(), "error the board can be at most 12x12");
+    bob.send("1,1");
+    assert_eq!(bob.next(), "error unknown command: 1,1");
+    alice.send("create");
+    alice.next();
+    alice.send("join 1");
+    assert_eq!(alice.next(), "error leave your game first");
+
+    bob.send("join 1");
+    bob.next();
+    alice.until("move");
+    alice.send("save /tmp/game.txt");
+    assert_eq!(alice.until("message"), "message Invalid command: 'save' is not available in network games; try again!");
+}
+
+#[test]
+fn test_client_that_does_not_read_holds_no_one_up() {
+    let address = lobby();
+    let _idle = Client::connect(address, "idle");
+    let mut alice = Client::connect(address, "alice");
+
+    // The chat of the lobby reaches the idle client too, filling up its connection.
+    let text = "x".repeat(2000);
+    for _ in 0..5000 {
+        alice.send(&format!("say {}", text));
+    }
+    alice.send("create");
+
+    assert_eq!(alice.until("created"), "created 1");
+}